@@ -0,0 +1,102 @@
+//! Measures walking the history of a large repository in topological order, as `retrieve` does,
+//! with and without a commit-graph file.
+//!
+//! Run with `cargo bench`. Requires `git` to write the commit-graph. The number of commits
+//! may be set with the `COMMITS` environment variable.
+
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+const DEFAULT_COMMITS: usize = 50_000;
+const RUNS: u32 = 5;
+
+fn create_repository(path: &Path, commits: usize) -> Result<(), git2::Error> {
+    let repo = git2::Repository::init(path)?;
+    let sig = git2::Signature::new("Bench", "bench@example.com", &git2::Time::new(0, 0))?;
+    let tree = repo.find_tree(repo.treebuilder(None)?.write()?)?;
+
+    let mut parent: Option<git2::Oid> = None;
+
+    for i in 0..commits {
+        let time = git2::Time::new(i as i64 * 60, 0);
+        let sig = git2::Signature::new(sig.name().unwrap(), sig.email().unwrap(), &time)?;
+        let parents = parent
+            .map(|oid| repo.find_commit(oid))
+            .transpose()?
+            .into_iter()
+            .collect::<Vec<_>>();
+        let parents = parents.iter().collect::<Vec<_>>();
+
+        let message = format!("Change {}", i);
+        parent = Some(repo.commit(None, &sig, &sig, &message, &tree, &parents)?);
+    }
+
+    repo.reference("refs/heads/master", parent.unwrap(), true, "bench")?;
+
+    Ok(())
+}
+
+fn walk(path: &Path) -> Result<Duration, git2::Error> {
+    let mut best = Duration::MAX;
+
+    for _ in 0..RUNS {
+        let start = Instant::now();
+
+        let repo = git2::Repository::open(path)?;
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push_ref("refs/heads/master")?;
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL)?;
+
+        // The first commit is only produced once the whole history has been walked.
+        revwalk.next();
+
+        best = best.min(start.elapsed());
+    }
+
+    Ok(best)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let commits = std::env::var("COMMITS")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(DEFAULT_COMMITS);
+
+    let path = std::env::temp_dir().join(format!("release-maker-bench-{}", std::process::id()));
+    create_repository(&path, commits)?;
+
+    let without = walk(&path)?;
+    println!(
+        "topological walk of {} commits without commit-graph: {:?}",
+        commits, without
+    );
+
+    let status = Command::new("git")
+        // libgit2 cannot read the generation data of newer versions of the commit-graph.
+        .args([
+            "-c",
+            "commitGraph.generationVersion=1",
+            "commit-graph",
+            "write",
+            "--reachable",
+        ])
+        .current_dir(&path)
+        .status()?;
+
+    if status.success() {
+        let with = walk(&path)?;
+        println!(
+            "topological walk of {} commits with commit-graph:    {:?} ({:.1}x)",
+            commits,
+            with,
+            without.as_secs_f64() / with.as_secs_f64()
+        );
+    } else {
+        eprintln!("`git commit-graph write` failed; skipping the measurement with commit-graph");
+    }
+
+    std::fs::remove_dir_all(&path)?;
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use chrono::{DateTime, Duration, FixedOffset, Local, Months, NaiveDate, NaiveDateTime, TimeZone};
+
+use std::convert::TryFrom;
+
+/// Parse a human-friendly expression of a point in time, akin to Git's "approxidate".
+///
+/// Supported expressions are:
+/// - `now`;
+/// - `today` and `yesterday`, denoting the start of the day in local time;
+/// - `<n> <unit> ago` and `last <unit>`, where the unit is one of `second`, `minute`,
+///   `hour`, `day`, `week`, `month`, or `year` (optionally plural);
+/// - dates, as `2024-05-01`, in local time;
+/// - dates with times, as `2024-05-01 12:30:00` or `2024-05-01T12:30:00`, in local time;
+/// - RFC 3339 timestamps, as `2024-05-01T12:30:00+02:00`;
+/// - Unix timestamps, as `@1714559400`.
+pub fn parse(s: &str) -> Result<DateTime<FixedOffset>, String> {
+    parse_relative_to(s, Local::now().fixed_offset())
+}
+
+fn start_of_day(date: NaiveDate) -> Result<DateTime<FixedOffset>, String> {
+    local(date.and_hms_opt(0, 0, 0).unwrap())
+}
+
+fn local(datetime: NaiveDateTime) -> Result<DateTime<FixedOffset>, String> {
+    Local
+        .from_local_datetime(&datetime)
+        .earliest()
+        .map(|datetime| datetime.fixed_offset())
+        .ok_or_else(|| format!("`{}` does not exist in the local time zone", datetime))
+}
+
+fn sub(now: DateTime<FixedOffset>, amount: i64, unit: &str) -> Option<DateTime<FixedOffset>> {
+    let unit = unit.strip_suffix('s').unwrap_or(unit);
+
+    let months = |n: i64| u32::try_from(n).ok().map(Months::new);
+
+    match unit {
+        "second" | "sec" => now.checked_sub_signed(Duration::try_seconds(amount)?),
+        "minute" | "min" => now.checked_sub_signed(Duration::try_minutes(amount)?),
+        "hour" => now.checked_sub_signed(Duration::try_hours(amount)?),
+        "day" => now.checked_sub_signed(Duration::try_days(amount)?),
+        "week" => now.checked_sub_signed(Duration::try_weeks(amount)?),
+        "month" => now.checked_sub_months(months(amount)?),
+        "year" => now.checked_sub_months(months(amount.checked_mul(12)?)?),
+        _ => None,
+    }
+}
+
+fn parse_relative_to(s: &str, now: DateTime<FixedOffset>) -> Result<DateTime<FixedOffset>, String> {
+    let expr = s.trim().to_lowercase();
+    let words = expr.split_whitespace().collect::<Vec<_>>();
+    let invalid = || format!("`{}` is not a valid point in time", s);
+
+    match words.as_slice() {
+        ["now"] => return Ok(now),
+        ["today"] => return start_of_day(now.with_timezone(&Local).date_naive()),
+        ["yesterday"] => {
+            let today = now.with_timezone(&Local).date_naive();
+            return start_of_day(today.pred_opt().ok_or_else(invalid)?);
+        }
+        ["last", unit] => return sub(now, 1, unit).ok_or_else(invalid),
+        [amount, unit, "ago"] => {
+            let amount = amount.parse::<i64>().map_err(|_| invalid())?;
+            return sub(now, amount, unit).ok_or_else(invalid);
+        }
+        _ => {}
+    }
+
+    let expr = s.trim();
+
+    if let Some(timestamp) = expr.strip_prefix('@') {
+        let timestamp = timestamp.parse::<i64>().map_err(|_| invalid())?;
+        let datetime = DateTime::from_timestamp(timestamp, 0).ok_or_else(invalid)?;
+        return Ok(datetime.fixed_offset());
+    }
+
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(expr) {
+        return Ok(datetime);
+    }
+
+    for format in &["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(expr, format) {
+            return local(datetime);
+        }
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(expr, "%Y-%m-%d") {
+        return start_of_day(date);
+    }
+
+    Err(invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339("2024-05-10T12:00:00+00:00").unwrap()
+    }
+
+    #[test]
+    fn relative() {
+        assert_eq!(parse_relative_to("now", now()), Ok(now()));
+        assert_eq!(
+            parse_relative_to("3 days ago", now()),
+            DateTime::parse_from_rfc3339("2024-05-07T12:00:00+00:00").map_err(|e| e.to_string())
+        );
+        assert_eq!(
+            parse_relative_to("Last Week", now()),
+            DateTime::parse_from_rfc3339("2024-05-03T12:00:00+00:00").map_err(|e| e.to_string())
+        );
+        assert_eq!(
+            parse_relative_to("2 months ago", now()),
+            DateTime::parse_from_rfc3339("2024-03-10T12:00:00+00:00").map_err(|e| e.to_string())
+        );
+        assert_eq!(
+            parse_relative_to("1 year ago", now()),
+            DateTime::parse_from_rfc3339("2023-05-10T12:00:00+00:00").map_err(|e| e.to_string())
+        );
+    }
+
+    #[test]
+    fn absolute() {
+        assert_eq!(
+            parse_relative_to("2024-05-01T12:30:00+02:00", now()),
+            DateTime::parse_from_rfc3339("2024-05-01T10:30:00+00:00").map_err(|e| e.to_string())
+        );
+        assert_eq!(
+            parse_relative_to("@1714559400", now()).map(|date| date.timestamp()),
+            Ok(1714559400)
+        );
+        assert!(parse_relative_to("2024-05-01", now()).is_ok());
+        assert!(parse_relative_to("2024-05-01 12:30:00", now()).is_ok());
+    }
+
+    #[test]
+    fn invalid() {
+        for s in &[
+            "",
+            "soon",
+            "3 fortnights ago",
+            "-1 months ago",
+            "@x",
+            "2024-13-01",
+        ] {
+            assert!(parse_relative_to(s, now()).is_err(), "{}", s);
+        }
+    }
+}
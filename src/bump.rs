@@ -0,0 +1,213 @@
+use crate::categorize::has_breaking_footer;
+use crate::release::{Release, Section};
+use crate::Result;
+
+use semver::{BuildMetadata, Prerelease, Version};
+
+use std::fs;
+use std::path::Path;
+
+/// The part of a semantic version that a release increments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bump {
+    /// Backwards compatible fixes.
+    Patch,
+    /// Backwards compatible features.
+    Minor,
+    /// Breaking changes.
+    Major,
+}
+
+impl Bump {
+    /// Determine the bump of a commit by its Conventional Commits type, from its summary and body.
+    ///
+    /// Breaking changes, marked by `!` after the type or by a `BREAKING CHANGE` footer, bump
+    /// the major version. Features (`feat`) bump the minor version, and all other commits
+    /// bump the patch version.
+    pub fn of_commit(summary: &str, body: &str) -> Self {
+        let breaking_footer = has_breaking_footer(body);
+
+        let prefix = match summary.split_once(": ") {
+            Some((prefix, _)) => prefix,
+            None if breaking_footer => return Bump::Major,
+            None => return Bump::Patch,
+        };
+
+        if breaking_footer || prefix.ends_with('!') {
+            return Bump::Major;
+        }
+
+        let kind = prefix.split('(').next().unwrap_or(prefix);
+
+        if kind.eq_ignore_ascii_case("feat") {
+            Bump::Minor
+        } else {
+            Bump::Patch
+        }
+    }
+
+    /// Determine the bump of a release by its sections, or `None` if it has no changes.
+    ///
+    /// Breaking and removed changes bump the major version, added and deprecated changes
+    /// bump the minor version, and changed, fixed, security, or custom changes bump the patch
+    /// version.
+    pub fn of_release(release: &Release) -> Option<Self> {
+        let parts = std::iter::once(release).chain(release.components.iter().map(|c| &c.release));
+
+        parts
+            .flat_map(|part| {
+                let builtin = Section::ALL
+                    .iter()
+                    .copied()
+                    .filter(move |&section| !part.section(section).is_empty())
+                    .map(|section| match section {
+                        Section::Breaking | Section::Removed => Bump::Major,
+                        Section::Added | Section::Deprecated => Bump::Minor,
+                        Section::Changed | Section::Fixed | Section::Security => Bump::Patch,
+                    });
+
+                let custom = part
+                    .sections
+                    .values()
+                    .filter(|custom| !custom.changes.is_empty())
+                    .map(|_| Bump::Patch);
+
+                builtin.chain(custom)
+            })
+            .max()
+    }
+
+    /// Returns the version following a version by this bump.
+    ///
+    /// Before 1.0.0, breaking changes bump the minor version and all other changes bump the
+    /// patch version, as the minor version is the one denoting incompatibility.
+    pub fn apply(self, version: &Version) -> Version {
+        let bump = match (version.major, self) {
+            (0, Bump::Major) => Bump::Minor,
+            (0, _) => Bump::Patch,
+            (_, bump) => bump,
+        };
+
+        let mut next = version.clone();
+        next.pre = Prerelease::EMPTY;
+        next.build = BuildMetadata::EMPTY;
+
+        // The release of a pre-release is the version itself.
+        if !version.pre.is_empty() {
+            return next;
+        }
+
+        match bump {
+            Bump::Major => {
+                next.major += 1;
+                next.minor = 0;
+                next.patch = 0;
+            }
+            Bump::Minor => {
+                next.minor += 1;
+                next.patch = 0;
+            }
+            Bump::Patch => next.patch += 1,
+        }
+
+        next
+    }
+}
+
+/// Set the version of the package of a `Cargo.toml` manifest, preserving the rest of the file.
+pub fn set_manifest_version(path: &Path, version: &Version) -> Result<()> {
+    let res = manifest_with_version(path, version)?;
+
+    fs::write(path, res).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+    Ok(())
+}
+
+/// Returns the text of a `Cargo.toml` manifest with the version of its package set, as it
+/// would be written by [`set_manifest_version`].
+///
+/// [`set_manifest_version`]: fn.set_manifest_version.html
+pub fn manifest_with_version(path: &Path, version: &Version) -> Result<String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+    let mut res = String::with_capacity(text.len());
+    let mut in_package = false;
+    let mut replaced = false;
+
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with('[') {
+            in_package = trimmed == "[package]";
+        } else if in_package && !replaced {
+            if let Some((key, _)) = trimmed.split_once('=') {
+                if key.trim() == "version" {
+                    let indent = &line[..line.len() - line.trim_start().len()];
+                    let newline = &line[line.trim_end().len()..];
+
+                    res.push_str(&format!("{}version = \"{}\"{}", indent, version, newline));
+                    replaced = true;
+                    continue;
+                }
+            }
+        }
+
+        res.push_str(line);
+    }
+
+    if !replaced {
+        return Err(format!("{}: the package has no version", path.display()).into());
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::release::Change;
+
+    #[test]
+    fn of_commit() {
+        assert_eq!(Bump::of_commit("fix: Crash", ""), Bump::Patch);
+        assert_eq!(Bump::of_commit("feat(model): Stickers", ""), Bump::Minor);
+        assert_eq!(Bump::of_commit("FEAT: Stickers", ""), Bump::Minor);
+        assert_eq!(Bump::of_commit("feat(model)!: Stickers", ""), Bump::Major);
+        assert_eq!(Bump::of_commit("refactor!: Builders", ""), Bump::Major);
+        assert_eq!(
+            Bump::of_commit("Rework the cache", "BREAKING CHANGE: no more cache"),
+            Bump::Major
+        );
+        assert_eq!(Bump::of_commit("Update dependencies", ""), Bump::Patch);
+    }
+
+    #[test]
+    fn of_release() {
+        let change = || Change::new("model", "Stickers", "Erk-", "2d3e585").unwrap();
+        let mut release = Release::default();
+
+        assert_eq!(Bump::of_release(&release), None);
+
+        release.fixed.push(change());
+        assert_eq!(Bump::of_release(&release), Some(Bump::Patch));
+
+        release.added.push(change());
+        assert_eq!(Bump::of_release(&release), Some(Bump::Minor));
+
+        release.removed.push(change());
+        assert_eq!(Bump::of_release(&release), Some(Bump::Major));
+    }
+
+    #[test]
+    fn apply() {
+        let version = |s| Version::parse(s).unwrap();
+
+        assert_eq!(Bump::Patch.apply(&version("1.2.3")), version("1.2.4"));
+        assert_eq!(Bump::Minor.apply(&version("1.2.3")), version("1.3.0"));
+        assert_eq!(Bump::Major.apply(&version("1.2.3")), version("2.0.0"));
+        assert_eq!(Bump::Major.apply(&version("0.2.3")), version("0.3.0"));
+        assert_eq!(Bump::Minor.apply(&version("0.2.3")), version("0.2.4"));
+        assert_eq!(Bump::Major.apply(&version("2.0.0-rc.1")), version("2.0.0"));
+        assert_eq!(Bump::Patch.apply(&version("1.2.3+build")), version("1.2.4"));
+    }
+}
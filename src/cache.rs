@@ -0,0 +1,265 @@
+use crate::categorize::Categorized;
+use crate::Result;
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the cache file, in the `.git` directory of a repository.
+pub const FILE_NAME: &str = "release-maker-cache";
+
+/// The version of the layout of the cache file. Caches of other versions are discarded.
+const VERSION: u32 = 4;
+
+/// The version of the layout of the files of the [`ApiCache`]. Caches of other versions
+/// are discarded.
+///
+/// [`ApiCache`]: struct.ApiCache.html
+const API_VERSION: u32 = 1;
+
+/// The categorization of a commit, with the note it was categorized with.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CachedCategorization {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    #[serde(flatten)]
+    categorized: Categorized,
+}
+
+/// What is known of a commit.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+struct Entry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    categorized: Option<CachedCategorization>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct CacheFile {
+    version: u32,
+    commits: HashMap<String, Entry>,
+}
+
+/// A cache of the metadata extracted from commits, so that repeated runs of `retrieve`
+/// only process new commits.
+#[derive(Debug, Default)]
+pub struct Cache {
+    /// The path to the cache file, or `None` if the cache is not persisted.
+    path: Option<PathBuf>,
+    file: CacheFile,
+    dirty: bool,
+}
+
+impl Cache {
+    /// Create a cache that is not persisted.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Open the cache in the `.git` directory of a repository.
+    ///
+    /// A missing, unreadable, or outdated cache is treated as empty.
+    pub fn open(git_dir: &Path) -> Self {
+        let path = git_dir.join(FILE_NAME);
+
+        let file = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<CacheFile>(&bytes).ok())
+            .filter(|file| file.version == VERSION)
+            .unwrap_or_default();
+
+        Self {
+            path: Some(path),
+            file,
+            dirty: false,
+        }
+    }
+
+    /// Return the cached categorization of a commit, if it was categorized with the same note.
+    pub fn categorized(&self, hash: &str, note: Option<&str>) -> Option<&Categorized> {
+        self.file
+            .commits
+            .get(hash)?
+            .categorized
+            .as_ref()
+            .filter(|cached| cached.note.as_deref() == note)
+            .map(|cached| &cached.categorized)
+    }
+
+    /// Cache the categorization of a commit with its note.
+    pub fn set_categorized(&mut self, hash: &str, note: Option<&str>, categorized: Categorized) {
+        self.file
+            .commits
+            .entry(hash.to_string())
+            .or_default()
+            .categorized = Some(CachedCategorization {
+            note: note.map(str::to_string),
+            categorized,
+        });
+        self.dirty = true;
+    }
+
+    /// Write the cache to its file, if it is persisted and has changed.
+    pub fn save(&mut self) -> Result<()> {
+        let path = match &self.path {
+            Some(path) if self.dirty => path,
+            _ => return Ok(()),
+        };
+
+        self.file.version = VERSION;
+
+        let mut bytes = serde_json::to_vec(&self.file)?;
+        bytes.push(b'\n');
+        fs::write(path, bytes).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+        self.dirty = false;
+
+        Ok(())
+    }
+}
+
+/// The directory of the user's caches: `$XDG_CACHE_HOME`, or else `~/.cache`.
+fn user_cache_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+}
+
+/// The path to the cache file of a GitHub repository, such as `owner/name`, in the directory
+/// of the [`ApiCache`].
+///
+/// [`ApiCache`]: struct.ApiCache.html
+fn repo_path(dir: &Path, repo: &str) -> PathBuf {
+    let mut path = dir.join("github");
+    path.extend(repo.split('/'));
+    path.set_extension("json");
+
+    path
+}
+
+/// What was retrieved from the GitHub API for a repository.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ApiCacheFile {
+    version: u32,
+    /// The pull requests that introduced commits, by their hashes.
+    #[serde(default)]
+    pull_requests: HashMap<String, Vec<u64>>,
+    /// The logins of the GitHub accounts of authors by their emails, or `None` for authors
+    /// known to have no account.
+    #[serde(default)]
+    logins: HashMap<String, Option<String>>,
+}
+
+#[derive(Debug, Default)]
+struct RepoCache {
+    file: ApiCacheFile,
+    dirty: bool,
+}
+
+/// A cache of lookups via the GitHub API, in `~/.cache/release-maker`, so that repeated runs
+/// do not repeat requests.
+///
+/// Lookups are kept in a file per repository, such as `github/owner/name.json`, which is read
+/// when the repository is first looked up.
+#[derive(Debug, Default)]
+pub struct ApiCache {
+    /// The directory of the cache files, or `None` if the cache is not persisted.
+    dir: Option<PathBuf>,
+    repos: HashMap<String, RepoCache>,
+}
+
+impl ApiCache {
+    /// Create a cache that is not persisted.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Open the cache in the user's cache directory, or create one that is not persisted
+    /// if there is no such directory.
+    pub fn open() -> Self {
+        Self {
+            dir: user_cache_dir().map(|dir| dir.join("release-maker")),
+            repos: HashMap::new(),
+        }
+    }
+
+    /// Return the cache of a repository, reading it from its file if it was not yet read.
+    ///
+    /// A missing, unreadable, or outdated file is treated as empty.
+    fn repo(&mut self, repo: &str) -> &mut RepoCache {
+        if !self.repos.contains_key(repo) {
+            let file = self
+                .dir
+                .as_ref()
+                .and_then(|dir| fs::read(repo_path(dir, repo)).ok())
+                .and_then(|bytes| serde_json::from_slice::<ApiCacheFile>(&bytes).ok())
+                .filter(|file| file.version == API_VERSION)
+                .unwrap_or_default();
+
+            let cache = RepoCache { file, dirty: false };
+            self.repos.insert(repo.to_string(), cache);
+        }
+
+        self.repos.get_mut(repo).unwrap()
+    }
+
+    /// Return the cached pull requests of a repository that introduced a commit.
+    pub fn pull_requests(&mut self, repo: &str, hash: &str) -> Option<&[u64]> {
+        self.repo(repo)
+            .file
+            .pull_requests
+            .get(hash)
+            .map(Vec::as_slice)
+    }
+
+    /// Cache the pull requests of a repository that introduced a commit.
+    pub fn set_pull_requests(&mut self, repo: &str, hash: &str, prs: Vec<u64>) {
+        let cache = self.repo(repo);
+        cache.file.pull_requests.insert(hash.to_string(), prs);
+        cache.dirty = true;
+    }
+
+    /// Return the cached login of the GitHub account of an author by their email.
+    ///
+    /// Returns `Some(None)` if the author is known to have no account.
+    pub fn login(&mut self, repo: &str, email: &str) -> Option<Option<&str>> {
+        let login = self.repo(repo).file.logins.get(email)?;
+        Some(login.as_deref())
+    }
+
+    /// Cache the login of the GitHub account of an author by their email.
+    pub fn set_login(&mut self, repo: &str, email: &str, login: Option<String>) {
+        let cache = self.repo(repo);
+        cache.file.logins.insert(email.to_string(), login);
+        cache.dirty = true;
+    }
+
+    /// Write the caches of the repositories that have changed to their files, if the cache
+    /// is persisted.
+    pub fn save(&mut self) -> Result<()> {
+        for (repo, cache) in &mut self.repos {
+            let path = match &self.dir {
+                Some(dir) if cache.dirty => repo_path(dir, repo),
+                _ => continue,
+            };
+
+            cache.file.version = API_VERSION;
+
+            let mut bytes = serde_json::to_vec(&cache.file)?;
+            bytes.push(b'\n');
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|err| format!("{}: {}", parent.display(), err))?;
+            }
+
+            fs::write(&path, bytes).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+            cache.dirty = false;
+        }
+
+        Ok(())
+    }
+}
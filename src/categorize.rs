@@ -0,0 +1,260 @@
+use crate::release::Section;
+
+use serde::{Deserialize, Serialize};
+
+/// The notes reference that overrides of the categorization of commits are read from.
+pub const NOTES_REF: &str = "refs/notes/release-maker";
+
+/// Describes how a commit is represented in a release.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Categorized {
+    /// The section the commit belongs to, or `None` if the commit is excluded.
+    pub section: Option<Section>,
+    /// The category of the change.
+    pub category: String,
+    /// The name of the change.
+    pub name: String,
+}
+
+impl Categorized {
+    /// Create the default representation of a commit, as an added change of any category.
+    pub fn new<I>(name: I) -> Self
+    where
+        I: Into<String>,
+    {
+        Self {
+            section: Some(Section::Added),
+            category: "any".to_string(),
+            name: name.into(),
+        }
+    }
+
+    /// Apply the Conventional Commits prefix of the name, such as `fix(gateway): ...`,
+    /// if it has one.
+    ///
+    /// The prefix is stripped from the name, and its scope becomes the category. The type
+    /// determines the section:
+    /// - `feat`: added.
+    /// - `fix`: fixed.
+    /// - `refactor`, `perf`, and `revert`: changed.
+    /// - `deprecate`: deprecated.
+    /// - `remove`: removed.
+    /// - `security`: security.
+    /// - `build`, `chore`, `ci`, `docs`, `style`, and `test`: excluded.
+    ///
+    /// A `!` after the type or scope, such as `feat(model)!: ...`, marks a breaking change,
+    /// which belongs to the breaking section whatever its type. Names with other types are
+    /// left as is.
+    pub fn apply_conventional(&mut self) {
+        let (prefix, summary) = match self.name.split_once(": ") {
+            Some(split) => split,
+            None => return,
+        };
+
+        let breaking = prefix.ends_with('!');
+        let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, scope)) => match scope.strip_suffix(')') {
+                Some(scope) if !scope.is_empty() => (kind, Some(scope)),
+                _ => return,
+            },
+            None => (prefix, None),
+        };
+
+        let section = match kind.to_lowercase().as_str() {
+            "feat" => Some(Section::Added),
+            "fix" => Some(Section::Fixed),
+            "refactor" | "perf" | "revert" => Some(Section::Changed),
+            "deprecate" => Some(Section::Deprecated),
+            "remove" => Some(Section::Removed),
+            "security" => Some(Section::Security),
+            "build" | "chore" | "ci" | "docs" | "style" | "test" => None,
+            _ => return,
+        };
+
+        let summary = summary.trim();
+        let mut chars = summary.chars();
+
+        let name = match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => return,
+        };
+
+        if let Some(scope) = scope {
+            self.category = scope.trim().to_string();
+        }
+
+        self.section = if breaking {
+            Some(Section::Breaking)
+        } else {
+            section
+        };
+        self.name = name;
+    }
+
+    /// Apply the `Changelog` trailer of the commit's message, if it has one.
+    ///
+    /// The value of the trailer is one of `breaking`, `added`, `changed`, `deprecated`, `fixed`,
+    /// `removed`, `security`, or `none` to exclude the commit. Without the trailer, a
+    /// `BREAKING CHANGE` footer moves the commit into the breaking section.
+    pub fn apply_trailers(&mut self, body: &str) {
+        if has_breaking_footer(body) {
+            self.section = Some(Section::Breaking);
+        }
+
+        let section = trailers(body)
+            .filter(|(key, _)| key.eq_ignore_ascii_case("changelog"))
+            .find_map(|(_, value)| parse_section(value));
+
+        if let Some(section) = section {
+            self.section = section;
+        }
+    }
+
+    /// Apply the overrides of a note attached to the commit.
+    ///
+    /// A note consists of `key: value` lines, where the key is one of:
+    /// - `section`: one of `breaking`, `added`, `changed`, `deprecated`, `fixed`, `removed`,
+    ///   `security`, or `none` to exclude the commit.
+    /// - `category`: the category of the change.
+    /// - `name`: the name of the change.
+    ///
+    /// A line of `exclude` on its own also excludes the commit. Other lines are ignored.
+    pub fn apply_note(&mut self, note: &str) {
+        for line in note.lines().map(str::trim) {
+            if line.eq_ignore_ascii_case("exclude") {
+                self.section = None;
+                continue;
+            }
+
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim().to_lowercase(), value.trim()),
+                None => continue,
+            };
+
+            if value.is_empty() {
+                continue;
+            }
+
+            match key.as_str() {
+                "section" => {
+                    if let Some(section) = parse_section(value) {
+                        self.section = section;
+                    }
+                }
+                "category" => self.category = value.to_string(),
+                "name" => self.name = value.to_string(),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Parse the name of a section, where `none` denotes exclusion.
+pub fn parse_section(s: &str) -> Option<Option<Section>> {
+    match s.to_lowercase().as_str() {
+        "breaking" => Some(Some(Section::Breaking)),
+        "added" => Some(Some(Section::Added)),
+        "changed" => Some(Some(Section::Changed)),
+        "deprecated" => Some(Some(Section::Deprecated)),
+        "fixed" => Some(Some(Section::Fixed)),
+        "removed" => Some(Some(Section::Removed)),
+        "security" => Some(Some(Section::Security)),
+        "none" => Some(None),
+        _ => None,
+    }
+}
+
+/// Returns whether the body of a commit's message has a `BREAKING CHANGE` footer, as defined
+/// by Conventional Commits.
+pub fn has_breaking_footer(body: &str) -> bool {
+    body.lines()
+        .any(|line| line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:"))
+}
+
+/// Returns the trailers of a commit's message, as `(key, value)` pairs.
+///
+/// Trailers are `Key: value` lines in the last paragraph of the message.
+pub fn trailers(body: &str) -> impl Iterator<Item = (&str, &str)> + '_ {
+    let paragraph = body.trim_end().rsplit("\n\n").next().unwrap_or("");
+
+    paragraph.lines().filter_map(|line| {
+        let (key, value) = line.split_once(':')?;
+
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return None;
+        }
+
+        Some((key, value.trim()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conventional(name: &str) -> Categorized {
+        let mut categorized = Categorized::new(name);
+        categorized.apply_conventional();
+        categorized
+    }
+
+    #[test]
+    fn apply_conventional() {
+        let fix = conventional("fix(gateway): reconnect on close");
+        assert_eq!(fix.section, Some(Section::Fixed));
+        assert_eq!(fix.category, "gateway");
+        assert_eq!(fix.name, "Reconnect on close");
+
+        let feat = conventional("feat: stickers");
+        assert_eq!(feat.section, Some(Section::Added));
+        assert_eq!(feat.category, "any");
+
+        assert_eq!(
+            conventional("perf(cache)!: drop the lock").section,
+            Some(Section::Breaking)
+        );
+        assert_eq!(conventional("chore: release").section, None);
+
+        for name in &["Add stickers", "wip: stickers", "fix(): crash", "fix: "] {
+            assert_eq!(conventional(name), Categorized::new(*name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn apply_trailers() {
+        let mut categorized = Categorized::new("Rework the cache");
+        categorized.apply_trailers("Details.\n\nBREAKING CHANGE: the cache is gone");
+        assert_eq!(categorized.section, Some(Section::Breaking));
+
+        let mut categorized = Categorized::new("Rework the cache");
+        categorized.apply_trailers("Details.\n\nChangelog: fixed\nSigned-off-by: Alex");
+        assert_eq!(categorized.section, Some(Section::Fixed));
+
+        let mut categorized = Categorized::new("Rework the cache");
+        categorized.apply_trailers("Changelog: none");
+        assert_eq!(categorized.section, None);
+
+        // Only the last paragraph holds trailers.
+        let mut categorized = Categorized::new("Rework the cache");
+        categorized.apply_trailers("Changelog: fixed\n\nMore details.");
+        assert_eq!(categorized.section, Some(Section::Added));
+    }
+
+    #[test]
+    fn apply_note() {
+        let mut categorized = Categorized::new("Rework the cache");
+        categorized
+            .apply_note("section: Changed\ncategory: cache\nname: Rework the cache\nnonsense");
+        assert_eq!(categorized.section, Some(Section::Changed));
+        assert_eq!(categorized.category, "cache");
+
+        categorized.apply_note("section: unknown\ncategory:");
+        assert_eq!(categorized.section, Some(Section::Changed));
+        assert_eq!(categorized.category, "cache");
+
+        categorized.apply_note("  Exclude  ");
+        assert_eq!(categorized.section, None);
+    }
+}
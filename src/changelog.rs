@@ -0,0 +1,307 @@
+use crate::store;
+
+/// A section of a changelog, describing one version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// The heading line of the section, such as `## [1.2.0] - 2024-05-01`.
+    pub heading: &'a str,
+    /// The content of the section after the heading, until the next section.
+    pub body: &'a str,
+}
+
+impl Section<'_> {
+    /// Returns the version the section describes, such as `1.2.0` for `## [1.2.0] - 2024-05-01`.
+    pub fn version(&self) -> &str {
+        heading_version(self.heading.trim_start_matches('#').trim())
+    }
+}
+
+/// Returns the level of a Markdown heading line and its text, or `None` if the line is not a heading.
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+
+    if level == 0 || level > 6 {
+        return None;
+    }
+
+    let rest = &line[level..];
+
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+
+    Some((level, rest.trim()))
+}
+
+/// Returns whether the text of a heading names a version, such as `[1.2.0] - 2024-05-01` or `v1.2`.
+fn is_version(text: &str) -> bool {
+    let bytes = text.as_bytes();
+
+    bytes
+        .windows(3)
+        .any(|w| w[0].is_ascii_digit() && w[1] == b'.' && w[2].is_ascii_digit())
+}
+
+/// Returns the version in the text of a heading, being either in brackets or the first word.
+fn heading_version(text: &str) -> &str {
+    match text.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or(rest),
+        None => text.split_whitespace().next().unwrap_or(text),
+    }
+}
+
+/// Returns whether the text of a heading marks unreleased changes, such as `[Unreleased]`.
+fn is_unreleased(text: &str) -> bool {
+    heading_version(text).eq_ignore_ascii_case("unreleased")
+}
+
+/// Returns the label and the URL of a link reference definition, such as `[1.2.0]: https://...`.
+fn parse_definition(line: &str) -> Option<(&str, &str)> {
+    let (label, url) = line.strip_prefix('[')?.split_once("]:")?;
+    let url = url.trim();
+
+    if label.is_empty() || url.is_empty() || url.contains(' ') {
+        return None;
+    }
+
+    Some((label, url))
+}
+
+/// Returns the byte offsets of the starts of all lines, paired with the lines,
+/// skipping the content of fenced code blocks.
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> + '_ {
+    let mut offset = 0;
+    let mut fenced = false;
+
+    text.split_inclusive('\n').filter_map(move |line| {
+        let start = offset;
+        offset += line.len();
+
+        let line = line.trim_end_matches(&['\n', '\r'][..]);
+
+        if line.trim_start().starts_with("```") {
+            fenced = !fenced;
+            return None;
+        }
+
+        if fenced {
+            None
+        } else {
+            Some((start, line))
+        }
+    })
+}
+
+/// Returns the section of the most recent version in a changelog.
+///
+/// The most recent version is the first heading that names a version. Headings such as the
+/// changelog's title or `## [Unreleased]` are skipped. The section ends at the next heading
+/// of the same or a higher level.
+pub fn latest_section(text: &str) -> Option<Section<'_>> {
+    sections(text).into_iter().next()
+}
+
+/// Returns the sections of all versions in a changelog, newest first.
+///
+/// The sections are found as by [`latest_section`].
+///
+/// [`latest_section`]: fn.latest_section.html
+pub fn sections(text: &str) -> Vec<Section<'_>> {
+    let headings = lines(text)
+        .filter_map(|(start, line)| {
+            parse_heading(line).map(|(level, title)| (start, line, level, title))
+        })
+        .collect::<Vec<_>>();
+
+    let mut sections = Vec::new();
+
+    for (i, &(start, heading, level, title)) in headings.iter().enumerate() {
+        if !is_version(title) {
+            continue;
+        }
+
+        let body_end = headings[i + 1..]
+            .iter()
+            .find(|&&(_, _, l, _)| l <= level)
+            .map_or(text.len(), |&(start, ..)| start);
+
+        sections.push(Section {
+            heading,
+            body: text[start + heading.len()..body_end].trim(),
+        });
+    }
+
+    sections
+}
+
+/// Insert the section of a version above the previous versions of a changelog, and update
+/// the link reference definitions at its bottom with `links`, as pairs of labels and URLs.
+///
+/// The section of the same `version` is replaced by the new section wherever it is. Otherwise,
+/// the section is inserted above the first version older than it, replacing an unreleased section
+/// if it is the newest.
+/// The definitions of `links` replace existing definitions of the same labels, and precede
+/// the remaining definitions. The rest of the changelog is preserved.
+pub fn insert_section(
+    text: &str,
+    version: Option<&str>,
+    section: &str,
+    links: &[(String, String)],
+) -> String {
+    // The link reference definitions, and blank lines between them, at the bottom.
+    let mut body_end = text.len();
+    let mut definitions = Vec::new();
+
+    for line in text.split_inclusive('\n').rev() {
+        let trimmed = line.trim_end_matches(&['\n', '\r'][..]);
+
+        if !trimmed.trim().is_empty() {
+            match parse_definition(trimmed) {
+                Some(definition) => definitions.push(definition),
+                None => break,
+            }
+        }
+
+        body_end -= line.len();
+    }
+
+    definitions.reverse();
+
+    let body = &text[..body_end];
+
+    let headings = lines(body)
+        .filter_map(|(start, line)| parse_heading(line).map(|(level, title)| (start, level, title)))
+        .collect::<Vec<_>>();
+
+    // The end of the section whose heading is at the index.
+    let section_end = |i: usize| {
+        let (_, level, _) = headings[i];
+
+        headings[i + 1..]
+            .iter()
+            .find(|&&(_, l, _)| l <= level)
+            .map_or(body.len(), |&(start, ..)| start)
+    };
+
+    // Whether the version of a heading is newer than the inserted version.
+    let newer = |title: &str| {
+        let existing = store::parse_version(heading_version(title));
+        let inserted = version.and_then(store::parse_version);
+
+        matches!((existing, inserted), (Some(existing), Some(inserted)) if existing > inserted)
+    };
+
+    let same = version.and_then(|version| {
+        headings
+            .iter()
+            .position(|&(_, _, title)| is_version(title) && heading_version(title) == version)
+    });
+
+    let first = headings
+        .iter()
+        .position(|&(_, _, title)| is_version(title) || is_unreleased(title));
+
+    let (start, end) = match (same, first) {
+        // The section of the same version is replaced wherever it is.
+        (Some(i), _) => (headings[i].0, section_end(i)),
+        // Versions older than the newest are inserted above the first older version.
+        (None, Some(i)) if headings[i..].iter().any(|&(_, _, title)| newer(title)) => {
+            let start = headings[i..]
+                .iter()
+                .find(|&&(_, _, title)| is_version(title) && !newer(title))
+                .map_or(body.len(), |&(start, ..)| start);
+
+            (start, start)
+        }
+        (None, Some(i)) => {
+            let (start, _, title) = headings[i];
+            let end = if is_unreleased(title) {
+                section_end(i)
+            } else {
+                start
+            };
+
+            (start, end)
+        }
+        (None, None) => (body.len(), body.len()),
+    };
+
+    let mut res = String::new();
+
+    let before = body[..start].trim_end();
+
+    if !before.is_empty() {
+        res.push_str(before);
+        res.push_str("\n\n");
+    }
+
+    res.push_str(section.trim_end());
+    res.push_str("\n\n");
+    res.push_str(body[end..].trim_end());
+
+    let mut res = res.trim_end().to_string();
+    res.push('\n');
+
+    let remaining = definitions
+        .into_iter()
+        .filter(|(label, _)| !links.iter().any(|(l, _)| l.eq_ignore_ascii_case(label)));
+
+    let mut definitions = links
+        .iter()
+        .map(|(label, url)| (label.as_str(), url.as_str()))
+        .chain(remaining)
+        .peekable();
+
+    if definitions.peek().is_some() {
+        res.push('\n');
+    }
+
+    for (label, url) in definitions {
+        res.push_str(&format!("[{}]: {}\n", label, url));
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "# Changelog\n\n## [Unreleased]\n\n- Pending.\n\n\
+        ## [v1.1.0]\n\n- Second.\n\n## [v1.0.0]\n\n- First.\n";
+
+    fn position(text: &str, needle: &str) -> usize {
+        text.find(needle)
+            .unwrap_or_else(|| panic!("missing {}", needle))
+    }
+
+    #[test]
+    fn replaces_unreleased() {
+        let text = insert_section(CHANGELOG, Some("v1.2.0"), "## [v1.2.0]\n\n- Third.\n", &[]);
+
+        assert!(!text.contains("Pending"));
+        assert!(position(&text, "v1.2.0") < position(&text, "v1.1.0"));
+    }
+
+    #[test]
+    fn replaces_same_version() {
+        let text = insert_section(CHANGELOG, Some("v1.0.0"), "## [v1.0.0]\n\n- Redone.\n", &[]);
+
+        assert!(!text.contains("First"));
+        assert!(text.contains("Pending"));
+        assert_eq!(text.matches("## [v1.0.0]").count(), 1);
+        assert_eq!(
+            insert_section(&text, Some("v1.0.0"), "## [v1.0.0]\n\n- Redone.\n", &[]),
+            text
+        );
+    }
+
+    #[test]
+    fn orders_older_versions() {
+        let text = insert_section(CHANGELOG, Some("v1.0.5"), "## [v1.0.5]\n\n- Patch.\n", &[]);
+
+        assert!(text.contains("Pending"));
+        assert!(position(&text, "v1.1.0") < position(&text, "v1.0.5"));
+        assert!(position(&text, "v1.0.5") < position(&text, "v1.0.0"));
+    }
+}
@@ -0,0 +1,494 @@
+use crate::github;
+use crate::locale::{Language, Strings};
+use crate::release::{AuthorFallback, Forge, Release, Section, UrlTemplates};
+use crate::{Format, Result};
+
+use serde::Deserialize;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The name of the configuration file, discovered in the root of a repository.
+pub const FILE_NAME: &str = ".release-maker.toml";
+
+/// The configuration of the tool, read from a TOML file.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Defaults of the options of `retrieve`.
+    pub retrieve: Retrieve,
+    /// Defaults of the options of `generate`, overridden by the selected profile.
+    pub generate: Profile,
+    /// Categorization of changes by the labels of their pull requests.
+    pub labels: Labels,
+    /// The style of the titles of changes, checked by `generate --lint`.
+    pub style: Style,
+    /// Repositories whose changes are aggregated into one release by `retrieve`.
+    pub repositories: Vec<RepositoryConfig>,
+    /// The GitHub instance and the credentials of its API.
+    pub github: GitHub,
+    /// Self-hosted Gitea and Forgejo instances.
+    pub gitea: Gitea,
+    /// Named bundles of options of `generate`, selected with `--profile`.
+    #[serde(rename = "profile")]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+impl Config {
+    /// Load the configuration from a file.
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let text =
+            std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+        toml::from_str(&text).map_err(|err| format!("{}: {}", path.display(), err).into())
+    }
+
+    /// Load the configuration from the `path` if it is present, or otherwise
+    /// from the configuration file in the `root` directory of a repository.
+    ///
+    /// If the configuration file does not exist in the root directory,
+    /// the default configuration is returned.
+    pub fn discover(root: &Path, path: Option<&Path>) -> Result<Self> {
+        if let Some(path) = path {
+            return Self::load(path);
+        }
+
+        let path = root.join(FILE_NAME);
+
+        if path.is_file() {
+            Self::load(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Returns the options of `generate` of the profile with the `name`, falling back to
+    /// the defaults of the `[generate]` table, or the defaults alone if the `name` is absent.
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile> {
+        let name = match name {
+            Some(name) => name,
+            None => return Ok(self.generate.clone()),
+        };
+
+        let profile = self
+            .profiles
+            .get(name)
+            .ok_or_else(|| format!("no profile named `{}` in the configuration", name))?;
+
+        Ok(profile.clone().or(self.generate.clone()))
+    }
+}
+
+/// Defaults of the options of `retrieve`.
+///
+/// ```toml
+/// [retrieve]
+/// branch = "main"
+/// remote = "upstream"
+/// repo_url = "https://github.com/owner/repo"
+/// exclude_authors = ["dependabot[bot]"]
+/// exclude_bots = true
+/// bots = ["*-ci", "release-bot"]
+///
+/// [retrieve.categories]
+/// gw = "gateway"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Retrieve {
+    /// The branch to retrieve commits from.
+    pub branch: Option<String>,
+    /// The remote whose branches commits are retrieved from, and whose URL is the repository's.
+    pub remote: Option<String>,
+    /// The URL to the repository, instead of the URL of the remote.
+    pub repo_url: Option<String>,
+    /// Authors, by name or email, whose commits are excluded. Compared case-insensitively.
+    pub exclude_authors: Vec<String>,
+    /// Whether the commits of bots are excluded.
+    pub exclude_bots: bool,
+    /// Patterns of the names or emails of bots, besides [`BOTS`], where `*` matches any text.
+    /// Compared case-insensitively.
+    ///
+    /// [`BOTS`]: constant.BOTS.html
+    pub bots: Vec<String>,
+    /// Renames of categories, such as the scopes of Conventional Commits.
+    pub categories: BTreeMap<String, String>,
+}
+
+/// Patterns of the names of bots whose commits are excluded with `exclude_bots`,
+/// such as `dependabot[bot]`, `renovate[bot]`, and `github-actions[bot]`.
+pub const BOTS: &[&str] = &["*[bot]"];
+
+impl Retrieve {
+    /// Returns whether the commits of an author are excluded.
+    pub fn is_excluded(&self, name: &str, email: &str) -> bool {
+        self.exclude_authors
+            .iter()
+            .any(|author| author.eq_ignore_ascii_case(name) || author.eq_ignore_ascii_case(email))
+            || (self.exclude_bots && self.is_bot(name, email))
+    }
+
+    /// Returns whether an author is a bot, by the patterns of [`BOTS`] and `bots`.
+    ///
+    /// [`BOTS`]: constant.BOTS.html
+    pub fn is_bot(&self, name: &str, email: &str) -> bool {
+        BOTS.iter()
+            .copied()
+            .chain(self.bots.iter().map(String::as_str))
+            .any(|pattern| wildcard_match(pattern, name) || wildcard_match(pattern, email))
+    }
+}
+
+/// Returns whether a text matches a pattern case-insensitively, where `*` matches any text.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let text = text.to_lowercase();
+
+    let mut parts = pattern.split('*');
+    // `split` always yields at least one part.
+    let first = parts.next().unwrap();
+
+    let mut rest = match text.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let mut parts = parts.collect::<Vec<_>>();
+
+    let last = match parts.pop() {
+        Some(last) => last,
+        // There is no `*` in the pattern.
+        None => return rest.is_empty(),
+    };
+
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+/// A rule assigning pull requests with a label to a section.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LabelRule {
+    /// The name of the label.
+    pub label: String,
+    /// The section of the release that pull requests with the label belong to.
+    pub section: Section,
+}
+
+/// Describes the outcome of categorizing a pull request by its labels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Categorization {
+    /// The pull request belongs to a section.
+    Section(Section),
+    /// The pull request is excluded from the release.
+    Exclude,
+}
+
+/// The GitHub instance and the credentials of its API.
+///
+/// ```toml
+/// [github]
+/// web_url = "https://github.example.com"
+/// api_url = "https://github.example.com/api/v3"
+/// token = "ghp_..."
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct GitHub {
+    /// The URL of a GitHub Enterprise Server, instead of GitHub itself.
+    pub web_url: Option<String>,
+    /// The URL of the REST API of a GitHub Enterprise Server, if it is not at `/api/v3`
+    /// of the `web_url`.
+    pub api_url: Option<String>,
+    /// The token to authenticate to the API with, used if no token is found in the environment
+    /// or the `gh` CLI.
+    pub token: Option<String>,
+}
+
+impl GitHub {
+    /// The instance given by `web_url` and `api_url`, or else by the table, which defaults
+    /// to GitHub itself.
+    pub fn instance(&self, web_url: Option<&str>, api_url: Option<&str>) -> github::Instance {
+        if web_url.is_some() || api_url.is_some() {
+            github::Instance::new(web_url, api_url)
+        } else {
+            github::Instance::new(self.web_url.as_deref(), self.api_url.as_deref())
+        }
+    }
+
+    /// Create a client of the `instance`, authenticating with the `token` if it is present,
+    /// or else with the token discovered for the instance, falling back to the token
+    /// of the table.
+    pub fn client(&self, instance: github::Instance, token: Option<String>) -> github::Client {
+        let token = token.or_else(|| github::discover_token(&instance, self.token.as_deref()));
+
+        github::Client::new(instance, token)
+    }
+}
+
+/// Self-hosted Gitea and Forgejo instances, whose repositories are not detected
+/// as such by their hosts.
+///
+/// ```toml
+/// [gitea]
+/// instances = ["https://git.example.com", "https://example.com/forgejo"]
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Gitea {
+    /// The URLs of the instances, including the path they are served under.
+    pub instances: Vec<String>,
+}
+
+impl Gitea {
+    /// Returns whether a URL to a repository is on one of the instances.
+    pub fn hosts(&self, url: &str) -> bool {
+        self.instances.iter().any(|instance| {
+            url.strip_prefix(instance.trim_end_matches('/'))
+                .is_some_and(|path| path.starts_with('/'))
+        })
+    }
+
+    /// Define the forge of a release and of its components as Gitea, if they define no forge
+    /// and are hosted on one of the instances.
+    pub fn detect(&self, release: &mut Release) {
+        if release.forge.is_none() && self.hosts(&release.repo_url) {
+            release.forge = Some(Forge::Gitea);
+        }
+
+        for component in &mut release.components {
+            self.detect(&mut component.release);
+        }
+    }
+}
+
+/// Categorization of changes by the labels of their pull requests.
+///
+/// ```toml
+/// [labels]
+/// exclude = ["skip-changelog"]
+/// fallback = "changed"
+///
+/// # Rules are ordered by priority; the first rule whose label a pull request has wins.
+/// [[labels.map]]
+/// label = "breaking"
+/// section = "changed"
+///
+/// [[labels.map]]
+/// label = "bug"
+/// section = "fixed"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Labels {
+    /// The rules, ordered by priority.
+    pub map: Vec<LabelRule>,
+    /// The section of pull requests whose labels match no rule.
+    ///
+    /// If absent, such pull requests remain in their section.
+    pub fallback: Option<Section>,
+    /// Labels that exclude pull requests from the release.
+    pub exclude: Vec<String>,
+}
+
+impl Labels {
+    /// Categorize a pull request by its labels.
+    ///
+    /// Returns `None` if the pull request should remain in its section.
+    pub fn categorize(&self, labels: &[String]) -> Option<Categorization> {
+        if labels.iter().any(|label| self.exclude.contains(label)) {
+            return Some(Categorization::Exclude);
+        }
+
+        self.map
+            .iter()
+            .find(|rule| labels.contains(&rule.label))
+            .map(|rule| rule.section)
+            .or(self.fallback)
+            .map(Categorization::Section)
+    }
+}
+
+/// The style of the titles of changes.
+///
+/// ```toml
+/// [style]
+/// max_length = 72
+/// redundant_verbs = ["Added", "Fixed", "Removed"]
+/// ```
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Style {
+    /// The maximum length of a title, in characters.
+    pub max_length: usize,
+    /// Verbs that titles should not start with, as they repeat the heading of their section.
+    ///
+    /// They are compared case-insensitively.
+    pub redundant_verbs: Vec<String>,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            max_length: 72,
+            redundant_verbs: Vec::new(),
+        }
+    }
+}
+
+/// A repository whose changes are aggregated into one release, as a component of it.
+///
+/// ```toml
+/// [[repositories]]
+/// name = "core"
+/// path = "../core"
+/// branch = "main"
+/// start = "v1.2.0"
+/// ```
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RepositoryConfig {
+    /// The name of the component, written as the heading of its changes.
+    pub name: String,
+    /// Path to the directory of the repository, relative to the repository of the configuration.
+    pub path: PathBuf,
+    /// The branch to retrieve commits from.
+    ///
+    /// If absent, the branch given to `retrieve` is used.
+    pub branch: Option<String>,
+    /// The commit to start retrieving from.
+    pub start: Option<String>,
+    /// The commit to stop retrieving at.
+    pub end: Option<String>,
+    /// Revisions whose reachable commits are excluded.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// A named bundle of options of `generate`, so that one repository can produce
+/// several flavors of notes from the same release.
+///
+/// Options given on the command line take precedence over those of the profile,
+/// which take precedence over those of the `[generate]` table.
+///
+/// ```toml
+/// [profile.github-release]
+/// format = "markdown"
+/// summary = true
+///
+/// [profile.website]
+/// format = "html"
+/// author_fallback = "mailto"
+/// output = "site/release.html"
+/// ```
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    /// The format of the output.
+    pub format: Option<Format>,
+    /// Path to the file where the output is written.
+    pub output: Option<PathBuf>,
+    /// Path to a directory where the output is written as `release.<extension>`.
+    pub out_dir: Option<PathBuf>,
+    /// Path to a Handlebars template of the output, taking precedence over the format.
+    pub template: Option<PathBuf>,
+    /// Write a summary of the release near the top.
+    pub summary: bool,
+    /// A template of the summary, implying `summary`.
+    pub summary_template: Option<String>,
+    /// A template of the URL to the profile of an author.
+    pub author_url: Option<String>,
+    /// A template of the URL to a commit, such as `{repo_url}/commit/{hash}`.
+    pub commit_url: Option<String>,
+    /// A template of the URL to a pull request, such as `{repo_url}/pull/{number}`.
+    pub pull_request_url: Option<String>,
+    /// A template of the URL to the comparison of two revisions,
+    /// such as `{repo_url}/compare/{from}...{to}`.
+    pub compare_url: Option<String>,
+    /// A template of the URL to a tag, such as `{repo_url}/releases/tag/{tag}`.
+    pub tag_url: Option<String>,
+    /// How to render authors that are not described by a username.
+    pub author_fallback: Option<AuthorFallback>,
+    /// The host of the repositories, which determines the links to commits and pull requests.
+    pub forge: Option<Forge>,
+    /// Warn about titles of changes that violate the `[style]`.
+    pub lint: bool,
+    /// Truncate titles of changes longer than a number of characters.
+    pub truncate_titles: Option<usize>,
+    /// Variables for placeholders in the intro and epilogue.
+    pub vars: BTreeMap<String, String>,
+    /// The names of sections in the order they are rendered.
+    pub section_order: Vec<String>,
+    /// Headings of sections by their names, overriding their usual headings.
+    pub headings: BTreeMap<String, String>,
+    /// The number of characters that commit hashes are abbreviated to.
+    pub hash_length: Option<usize>,
+    /// Whether categories and names of changes are written without escaping Markdown.
+    pub raw_names: bool,
+    /// The language of the headings of sections and the other texts of the output.
+    pub lang: Option<Language>,
+    /// The texts of the output besides the headings of sections, overriding those of the language.
+    pub strings: Strings,
+}
+
+impl Profile {
+    /// Fall back to the options of `defaults` for options absent from this profile.
+    pub fn or(self, defaults: Profile) -> Profile {
+        let mut vars = defaults.vars;
+        vars.extend(self.vars);
+
+        let mut headings = defaults.headings;
+        headings.extend(self.headings);
+
+        let section_order = if self.section_order.is_empty() {
+            defaults.section_order
+        } else {
+            self.section_order
+        };
+
+        Profile {
+            format: self.format.or(defaults.format),
+            output: self.output.or(defaults.output),
+            out_dir: self.out_dir.or(defaults.out_dir),
+            template: self.template.or(defaults.template),
+            summary: self.summary || defaults.summary,
+            summary_template: self.summary_template.or(defaults.summary_template),
+            author_url: self.author_url.or(defaults.author_url),
+            commit_url: self.commit_url.or(defaults.commit_url),
+            pull_request_url: self.pull_request_url.or(defaults.pull_request_url),
+            compare_url: self.compare_url.or(defaults.compare_url),
+            tag_url: self.tag_url.or(defaults.tag_url),
+            author_fallback: self.author_fallback.or(defaults.author_fallback),
+            forge: self.forge.or(defaults.forge),
+            lint: self.lint || defaults.lint,
+            truncate_titles: self.truncate_titles.or(defaults.truncate_titles),
+            vars,
+            section_order,
+            headings,
+            hash_length: self.hash_length.or(defaults.hash_length),
+            raw_names: self.raw_names || defaults.raw_names,
+            lang: self.lang.or(defaults.lang),
+            strings: self.strings.or(defaults.strings),
+        }
+    }
+
+    /// Return the templates of the URLs to commits, pull requests, comparisons, and tags.
+    pub fn url_templates(&self) -> UrlTemplates {
+        UrlTemplates {
+            commit_url: self.commit_url.clone(),
+            pull_request_url: self.pull_request_url.clone(),
+            compare_url: self.compare_url.clone(),
+            tag_url: self.tag_url.clone(),
+        }
+    }
+}
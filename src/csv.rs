@@ -0,0 +1,111 @@
+use crate::release::{Change, OneOrMore, Release};
+
+use std::fmt;
+
+/// The columns of every change, as written by [`generate_csv`].
+///
+/// [`generate_csv`]: fn.generate_csv.html
+const COLUMNS: [&str; 7] = [
+    "section",
+    "category",
+    "title",
+    "authors",
+    "commits",
+    "hashes",
+    "description",
+];
+
+/// Write a record of comma- or tab-separated values, terminated by a newline.
+///
+/// Fields containing the separator, quotes, or newlines are quoted, and quotes within them are doubled.
+pub fn write_record<I, T>(source: &mut dyn fmt::Write, sep: char, fields: I) -> fmt::Result
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<str>,
+{
+    for (i, field) in fields.into_iter().enumerate() {
+        if i != 0 {
+            source.write_char(sep)?;
+        }
+
+        let field = field.as_ref();
+
+        if field.contains([sep, '"', '\n', '\r']) {
+            write!(source, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            source.write_str(field)?;
+        }
+    }
+
+    source.write_char('\n')
+}
+
+/// Write the header of the rows written by [`generate_csv`].
+///
+/// If `versioned` is true, the rows are preceded by a column for the version of their release.
+///
+/// [`generate_csv`]: fn.generate_csv.html
+pub fn write_header(source: &mut dyn fmt::Write, sep: char, versioned: bool) -> fmt::Result {
+    let version = if versioned { Some("version") } else { None };
+
+    write_record(source, sep, version.into_iter().chain(COLUMNS))
+}
+
+/// Generate one row per change of a [`Release`], by writing to a source implementing
+/// [`std::fmt::Write`]
+///
+/// Authors are separated by commas, and commits (as links) and their hashes by spaces.
+/// The changes of components follow those of the release itself.
+/// If a `version` is given, it precedes every row.
+///
+/// [`Release`]: ../release/struct.Release.html
+/// [`std::fmt::Write`]: std::fmt::Write
+pub fn generate_csv(
+    source: &mut dyn fmt::Write,
+    sep: char,
+    version: Option<&str>,
+    rel: &Release,
+) -> fmt::Result {
+    for section in rel.sections() {
+        for change in section.changes {
+            let Change(category, name, OneOrMore(authors), OneOrMore(commits), description) =
+                change;
+
+            let authors = authors
+                .iter()
+                .map(|author| author.name())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let links = commits
+                .iter()
+                .map(|commit| rel.commit_url(commit.hash()))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let hashes = commits
+                .iter()
+                .map(|commit| commit.hash())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let row = [
+                section.name,
+                category,
+                name,
+                &authors,
+                &links,
+                &hashes,
+                description.as_deref().unwrap_or(""),
+            ];
+
+            write_record(source, sep, version.into_iter().chain(row))?;
+        }
+    }
+
+    for component in &rel.components {
+        generate_csv(source, sep, version, &component.release)?;
+    }
+
+    Ok(())
+}
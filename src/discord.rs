@@ -0,0 +1,266 @@
+use crate::release::{
+    self, escape_markdown, Author, AuthorLink, Change, OneOrMore, PullRequest, Release,
+};
+
+use std::fmt;
+
+/// The maximum number of characters of a Discord message.
+pub const MESSAGE_LIMIT: usize = 2000;
+
+fn write_author(source: &mut dyn fmt::Write, rel: &Release, author: &Author) -> fmt::Result {
+    let name = escape_markdown(author.name());
+
+    match rel.get_author_link(author) {
+        Some(AuthorLink::Profile(url)) => write!(source, "[@{}](<{}>)", name, url),
+        Some(AuthorLink::Mail(url)) => write!(source, "[{}](<{}>)", name, url),
+        None => source.write_str(&name),
+    }
+}
+
+/// Write a list of changes of `part`, which is either the release `rel` or one of its components,
+/// under a header.
+fn write_list(
+    source: &mut dyn fmt::Write,
+    header: &str,
+    rel: &Release,
+    part: &Release,
+    changes: &[Change],
+) -> fmt::Result {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(source, "**{}**", escape_markdown(header))?;
+
+    for change in changes {
+        let Change(category, name, OneOrMore(authors), OneOrMore(commits), _) = change;
+
+        let (name, pr) = PullRequest::split(name);
+
+        write!(
+            source,
+            "- [{}] {} (",
+            rel.markdown_text(category),
+            rel.markdown_text(name)
+        )?;
+
+        for (i, author) in authors.iter().enumerate() {
+            if i != 0 {
+                source.write_str(" ")?;
+            }
+
+            write_author(source, rel, author)?;
+        }
+
+        source.write_str(")")?;
+
+        for commit in commits {
+            write!(
+                source,
+                " [`{}`](<{}>)",
+                rel.short_hash(commit),
+                part.commit_url(commit.hash())
+            )?;
+        }
+
+        if let Some(PullRequest(number)) = pr {
+            write!(
+                source,
+                " [{}](<{}>)",
+                part.pull_request_ref(number),
+                part.pull_request_url(number)
+            )?;
+        }
+
+        writeln!(source)?;
+    }
+
+    writeln!(source)
+}
+
+/// Write the message of a release, preceded by its heading or else by `version`.
+fn write_message(source: &mut dyn fmt::Write, version: Option<&str>, rel: &Release) -> fmt::Result {
+    if let Some(heading) = rel.heading().or_else(|| version.map(str::to_string)) {
+        writeln!(source, "**{}**\n", escape_markdown(&heading))?;
+    }
+
+    if let Some(intro) = &rel.intro {
+        writeln!(source, "{}\n", intro.trim_end())?;
+    }
+
+    if let Some(summary) = &rel.summary {
+        writeln!(source, "{}\n", summary.trim_end())?;
+    }
+
+    writeln!(source, "{}", rel.strings.contributors())?;
+
+    for author in rel.sorted_authors() {
+        write!(source, "- ")?;
+        write_author(source, rel, author)?;
+        writeln!(source)?;
+    }
+
+    writeln!(source)?;
+
+    let mut reviewers = rel.reviewers.iter().collect::<Vec<_>>();
+    release::sort_authors(&mut reviewers);
+
+    if !reviewers.is_empty() {
+        writeln!(source, "{}", rel.strings.reviewers())?;
+
+        for reviewer in &reviewers {
+            write!(source, "- ")?;
+            write_author(source, rel, reviewer)?;
+            writeln!(source)?;
+        }
+
+        writeln!(source)?;
+    }
+
+    for section in rel.sections() {
+        write_list(source, &section.heading, rel, rel, section.changes)?;
+    }
+
+    for component in &rel.components {
+        let part = &component.release;
+
+        for section in rel.sections_of(part) {
+            let header = format!("{}: {}", component.name, section.heading);
+            write_list(source, &header, rel, part, section.changes)?;
+        }
+    }
+
+    if !rel.new_contributors.is_empty() {
+        writeln!(
+            source,
+            "**{}**",
+            escape_markdown(rel.strings.new_contributors())
+        )?;
+
+        for contributor in &rel.new_contributors {
+            write!(source, "- ")?;
+            write_author(source, rel, &contributor.author)?;
+            writeln!(
+                source,
+                " {} <{}>",
+                rel.strings.first_contribution(),
+                rel.contribution_url(contributor)
+            )?;
+        }
+
+        writeln!(source)?;
+    }
+
+    if let Some(epilogue) = &rel.epilogue {
+        writeln!(source, "{}\n", epilogue.trim_end())?;
+    }
+
+    if let Some(url) = rel.full_changelog_url() {
+        writeln!(source, "**{}**: <{}>", rel.strings.full_changelog(), url)?;
+    }
+
+    Ok(())
+}
+
+/// Generate the output message from a [`Release`] as Markdown for Discord, by writing to a
+/// source implementing [`std::fmt::Write`]
+///
+/// Discord lacks reference-style links, so links are written inline, and enclosed in angle
+/// brackets so that they are not embedded. Headings are written in bold.
+///
+/// As Discord limits messages to [`MESSAGE_LIMIT`] characters, longer output is split into
+/// several messages, each preceded by a line such as `--- Message 1/3 ---` that is not part of it.
+/// If a `version` is given and the release has no heading of its own, it precedes the message.
+///
+/// [`Release`]: ../release/struct.Release.html
+/// [`std::fmt::Write`]: std::fmt::Write
+/// [`MESSAGE_LIMIT`]: constant.MESSAGE_LIMIT.html
+pub fn generate_discord(
+    source: &mut dyn fmt::Write,
+    version: Option<&str>,
+    rel: &Release,
+) -> fmt::Result {
+    let mut msg = String::new();
+    write_message(&mut msg, version, rel)?;
+
+    let chunks = split_message(&msg, MESSAGE_LIMIT);
+
+    if let [chunk] = chunks.as_slice() {
+        return writeln!(source, "{}", chunk);
+    }
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        if i != 0 {
+            writeln!(source)?;
+        }
+
+        writeln!(
+            source,
+            "--- Message {}/{} ---\n{}",
+            i + 1,
+            chunks.len(),
+            chunk
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Split a message into chunks of at most `limit` characters, such as to post it as several
+/// Discord messages.
+///
+/// Chunks are split between paragraphs, or between the lines of paragraphs longer than `limit`,
+/// or within lines longer than `limit`.
+pub fn split_message(msg: &str, limit: usize) -> Vec<String> {
+    let paragraphs = msg.trim().split("\n\n").flat_map(|paragraph| {
+        if paragraph.chars().count() <= limit {
+            vec![paragraph.to_string()]
+        } else {
+            let lines = paragraph.split('\n').flat_map(|line| {
+                line.chars()
+                    .collect::<Vec<_>>()
+                    .chunks(limit)
+                    .map(|part| part.iter().collect::<String>())
+                    .collect::<Vec<_>>()
+            });
+
+            pack(lines, "\n", limit)
+        }
+    });
+
+    pack(paragraphs, "\n\n", limit)
+}
+
+/// Join pieces of at most `limit` characters by `sep` into as few chunks of at most `limit`
+/// characters as possible, in order.
+fn pack<I>(pieces: I, sep: &str, limit: usize) -> Vec<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    let mut len = 0;
+
+    for piece in pieces {
+        let piece_len = piece.chars().count();
+
+        if !chunk.is_empty() && len + sep.len() + piece_len > limit {
+            chunks.push(std::mem::take(&mut chunk));
+            len = 0;
+        }
+
+        if !chunk.is_empty() {
+            chunk.push_str(sep);
+            len += sep.len();
+        }
+
+        chunk.push_str(&piece);
+        len += piece_len;
+    }
+
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+
+    chunks
+}
@@ -0,0 +1,84 @@
+use thiserror::Error;
+
+/// The errors of reading commits from repositories and of processing releases.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// An error of the Git library.
+    #[error("{}", .0.message())]
+    Git(#[from] git2::Error),
+    /// A hash that is not a full or abbreviated hexadecimal commit hash.
+    #[error("`{0}` is not a commit hash")]
+    InvalidHash(String),
+    /// An abbreviated hash that is the prefix of several objects.
+    #[error("commit hash `{0}` is ambiguous; use more characters")]
+    AmbiguousHash(String),
+    /// A hash that matches no object.
+    #[error("no commit matches the hash `{0}`")]
+    UnknownHash(String),
+    /// A hash of an object that is not a commit.
+    #[error("`{0}` does not identify a commit")]
+    NotACommit(String),
+    /// A starting boundary that is not reachable from the branch.
+    #[error("the start commit `{0}` is not on the branch")]
+    StartNotOnBranch(String),
+    /// An ending boundary that is not reachable from the starting boundary.
+    #[error("the end commit `{0}` is not reachable from the start of the list")]
+    EndNotReachable(String),
+    /// A revision that cannot be resolved to a commit.
+    #[error("{revision}: {message}")]
+    InvalidRevision { revision: String, message: String },
+    /// A tag that does not exist.
+    #[error("no tag named `{0}`")]
+    UnknownTag(String),
+    /// A remote that does not exist.
+    #[error("no remote named `{0}`; list the remotes with `git remote -v`")]
+    UnknownRemote(String),
+    /// A remote without a URL that is valid UTF-8.
+    #[error("the URL of the remote `{0}` is missing or not valid UTF-8")]
+    InvalidRemoteUrl(String),
+    /// A branch that does not exist, along with the branches that do.
+    #[error(
+        "no branch `{branch}`; fetch it from the remote with `git fetch {remote}`{}",
+        available(.branches)
+    )]
+    UnknownBranch {
+        remote: String,
+        branch: String,
+        branches: Vec<String>,
+    },
+    /// A branch that the remote lacks, which is therefore not fetched.
+    #[error(
+        "the remote `{remote}` has no branch `{branch}`; push it, or retrieve the local branch \
+         without `--fetch`"
+    )]
+    UnknownRemoteBranch { remote: String, branch: String },
+    /// A repository whose default branch cannot be determined.
+    #[error(
+        "cannot determine the default branch of the remote `{remote}`; give it with `--branch`{}",
+        available(.branches)
+    )]
+    NoDefaultBranch {
+        remote: String,
+        branches: Vec<String>,
+    },
+    /// A part of a commit that is not valid UTF-8.
+    #[error("the {part} of commit {hash} is not valid UTF-8")]
+    InvalidUtf8 { hash: String, part: &'static str },
+    /// A commit whose time is out of range.
+    #[error("the time of commit {0} is invalid")]
+    InvalidTime(String),
+    /// A URL that does not point to a repository.
+    #[error("`{0}` is not a URL to a repository, such as `https://github.com/owner/repo`")]
+    InvalidUrl(String),
+    /// A change without a category, which the output requires.
+    #[error("the change \"{name}\" of the `{section}` section has an empty category")]
+    EmptyCategory { section: String, name: String },
+}
+
+fn available(branches: &[String]) -> String {
+    if branches.is_empty() {
+        String::new()
+    } else {
+        format!(" (available branches: {})", branches.join(", "))
+    }
+}
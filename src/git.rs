@@ -1,6 +1,13 @@
+use crate::error::Error;
+use crate::release::normalize_url;
 use crate::Result;
 
-use std::path::Path;
+use chrono::{DateTime, FixedOffset};
+use tracing::{debug, info, trace, warn};
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 /// Defines a Git user.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -15,12 +22,154 @@ pub struct Commit {
     pub hash: String,
     pub author: User,
     pub committer: User,
+    /// The time the commit was committed.
+    pub time: DateTime<FixedOffset>,
+    /// The summary of the commit, i.e. the first line of its message.
     pub message: String,
+    /// The rest of the commit's message after the summary, such as its trailers.
+    pub body: String,
+    /// The note attached to the commit, if notes are read (see [`Commits::notes`]).
+    ///
+    /// [`Commits::notes`]: struct.Commits.html#method.notes
+    pub note: Option<String>,
+}
+
+impl Commit {
+    /// Returns the co-authors of the commit, as credited by `Co-authored-by: Name <email>`
+    /// trailers in its message.
+    pub fn co_authors(&self) -> Vec<User> {
+        crate::categorize::trailers(&self.body)
+            .filter(|(key, _)| key.eq_ignore_ascii_case("co-authored-by"))
+            .filter_map(|(_, value)| {
+                let (name, email) = value.strip_suffix('>')?.split_once('<')?;
+                let name = name.trim();
+
+                if name.is_empty() {
+                    return None;
+                }
+
+                Some(User {
+                    name: name.to_string(),
+                    email: email.trim().to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the number and the title of the pull request merged by the commit, if it is
+    /// the merge commit of a pull request on GitHub (`Merge pull request #123 from ...`) or
+    /// of a merge request on GitLab (`See merge request group/project!123`).
+    ///
+    /// The title is the first line of the body, as written by GitHub and GitLab,
+    /// or the summary if the body is empty.
+    pub fn merged_pull_request(&self) -> Option<(u64, &str)> {
+        let number = match self.message.strip_prefix("Merge pull request #") {
+            Some(rest) => rest.split_whitespace().next()?.parse().ok()?,
+            None if self.message.starts_with("Merge branch ") => self
+                .body
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("See merge request "))?
+                .rsplit('!')
+                .next()?
+                .parse()
+                .ok()?,
+            None => return None,
+        };
+
+        let title = self
+            .body
+            .lines()
+            .next()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("See merge request "))
+            .unwrap_or(&self.message);
+
+        Some((number, title))
+    }
+}
+
+/// Describes whether the history of a repository is walked with the help of a commit-graph file,
+/// which greatly speeds up walking large histories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitGraph {
+    /// A commit-graph file is present and used.
+    Usable,
+    /// A commit-graph is present, but cannot be used, such as a split commit-graph
+    /// or one with generation data (written by default since Git 2.31).
+    Unsupported,
+    /// There is no commit-graph.
+    Missing,
+}
+
+/// Describes the kind of branches that commits are retrieved from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Branches {
+    /// A local branch (`refs/heads/<branch>`), or else the remote-tracking branch of the remote.
+    Any,
+    /// Only local branches (`refs/heads/<branch>`).
+    Local,
+    /// Only the remote-tracking branches of the remote (`refs/remotes/<remote>/<branch>`).
+    Remote,
+}
+
+/// Defines a Git tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    /// The hash of the commit the tag points to.
+    pub hash: String,
+    /// The time of the commit the tag points to.
+    pub time: DateTime<FixedOffset>,
+}
+
+fn commit_time(commit: &git2::Commit<'_>) -> Result<DateTime<FixedOffset>, Error> {
+    let time = commit.time();
+    let invalid = || Error::InvalidTime(commit.id().to_string());
+
+    let offset = FixedOffset::east_opt(time.offset_minutes() * 60).ok_or_else(invalid)?;
+    let time = DateTime::from_timestamp(time.seconds(), 0).ok_or_else(invalid)?;
+
+    Ok(time.with_timezone(&offset))
+}
+
+/// Split the message of a commit into its summary and the rest.
+pub fn split_message(message: &str) -> (String, String) {
+    let mut lines = message.splitn(2, '\n');
+    let summary = lines.next().unwrap_or("").trim().to_string();
+    let body = lines.next().unwrap_or("").trim().to_string();
+
+    (summary, body)
+}
+
+/// Look up the commit identified by a full or abbreviated hash in the object database.
+fn resolve_hash(repo: &git2::Repository, hash: &str) -> Result<git2::Oid, Error> {
+    if hash.is_empty() || hash.len() > 40 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::InvalidHash(hash.to_string()));
+    }
+
+    let oid = repo
+        .odb()?
+        .exists_prefix(git2::Oid::from_str(hash)?, hash.len())
+        .map_err(|err| match err.code() {
+            git2::ErrorCode::Ambiguous => Error::AmbiguousHash(hash.to_string()),
+            git2::ErrorCode::NotFound => Error::UnknownHash(hash.to_string()),
+            _ => Error::InvalidRevision {
+                revision: hash.to_string(),
+                message: err.message().to_string(),
+            },
+        })?;
+
+    repo.find_commit(oid)
+        .map_err(|_| Error::NotACommit(hash.to_string()))?;
+
+    Ok(oid)
 }
 
 /// Defines an iterator of [`Commit`]s.
 ///
 /// The range of commits may be configuring using [`start`] and/or [`end`].
+/// Authors and committers are mapped to their canonical identities by the
+/// repository's `.mailmap`, if it has one.
 ///
 /// [`Commit`]: struct.Commit.html
 /// [`start`]: #method.start
@@ -28,71 +177,242 @@ pub struct Commit {
 pub struct Commits<'a> {
     repo: &'a git2::Repository,
     inner: git2::Revwalk<'a>,
+    /// The commit the walk begins at.
+    tip: git2::Oid,
     end: git2::Oid,
+    notes: Option<String>,
+    /// The mailmap of the repository, which maps the identities of authors and committers
+    /// to their canonical identities.
+    mailmap: Option<git2::Mailmap>,
+    /// Patterns of paths, of which commits must change at least one to be listed.
+    paths: Vec<String>,
+    /// Whether merge commits are skipped.
+    no_merges: bool,
 }
 
 impl Commits<'_> {
-    /// Defines the starting boundary for the commit list with a hash.
+    /// Defines the starting boundary for the commit list with a full or abbreviated hash.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the string is empty, is longer than 40 hex
-    /// characters, or contains any non-hex characters.
-    pub fn start(mut self, hash: &str) -> Self {
-        self.inner.reset().unwrap();
-        self.inner.push(git2::Oid::from_str(hash).unwrap()).unwrap();
-        self
+    /// Returns an error if the hash does not identify exactly one commit, or if
+    /// the commit is not reachable from the branch.
+    pub fn start(mut self, hash: &str) -> Result<Self, Error> {
+        let oid = resolve_hash(self.repo, hash)?;
+
+        if oid != self.tip && !self.repo.graph_descendant_of(self.tip, oid)? {
+            return Err(Error::StartNotOnBranch(hash.to_string()));
+        }
+
+        self.inner.reset()?;
+        self.inner.push(oid)?;
+        self.tip = oid;
+        Ok(self)
     }
 
-    /// Defines the ending boundary (inclusive) for the commit list with a hash.
+    /// Defines the ending boundary (inclusive) for the commit list with a full or abbreviated hash.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the string is empty, is longer than 40 hex
-    /// characters, or contains any non-hex characters.
-    pub fn end(mut self, hash: &str) -> Self {
-        self.end = git2::Oid::from_str(hash).unwrap();
+    /// Returns an error if the hash does not identify exactly one commit, or if
+    /// the commit is not reachable from the starting boundary.
+    pub fn end(mut self, hash: &str) -> Result<Self, Error> {
+        let oid = resolve_hash(self.repo, hash)?;
+
+        if oid != self.tip && !self.repo.graph_descendant_of(self.tip, oid)? {
+            return Err(Error::EndNotReachable(hash.to_string()));
+        }
+
+        self.end = oid;
+        Ok(self)
+    }
+
+    /// Exclude the commits reachable from a revision, such as a branch, a tag, or a hash,
+    /// for example to omit the history of a merged branch of imported code.
+    ///
+    /// Must be called after [`start`], which resets the exclusions.
+    ///
+    /// [`start`]: #method.start
+    pub fn exclude(mut self, rev: &str) -> Result<Self, Error> {
+        let oid = self
+            .repo
+            .revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|err| Error::InvalidRevision {
+                revision: rev.to_string(),
+                message: err.message().to_string(),
+            })?
+            .id();
+
+        self.inner.hide(oid)?;
+        Ok(self)
+    }
+
+    /// Returns the name of the most recent tag reachable from the starting boundary,
+    /// or `None` if there is no such tag.
+    pub fn last_tag(&self) -> Result<Option<String>, Error> {
+        let commit = self.repo.find_commit(self.tip)?;
+
+        let describe = match commit
+            .as_object()
+            .describe(git2::DescribeOptions::new().describe_tags())
+        {
+            Ok(describe) => describe,
+            Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let name = describe.format(Some(git2::DescribeFormatOptions::new().abbreviated_size(0)))?;
+        Ok(Some(name))
+    }
+
+    /// Skip merge commits, such as `Merge branch 'main'`.
+    pub fn no_merges(mut self) -> Self {
+        self.no_merges = true;
+        self
+    }
+
+    /// Only follow the first parent of merge commits, listing the commits of the branch itself
+    /// and the merges into it, but not the commits of the merged branches.
+    pub fn first_parent(mut self) -> Result<Self, Error> {
+        self.inner.simplify_first_parent()?;
+        Ok(self)
+    }
+
+    /// Only list commits that change a path matching one of the patterns, such as `src/gateway`
+    /// or `*.md`, compared to their first parent.
+    pub fn paths(mut self, patterns: &[String]) -> Self {
+        self.paths = patterns.to_vec();
+        self
+    }
+
+    /// Returns whether a commit changes a path matching the patterns of [`paths`],
+    /// or whether there are no patterns.
+    ///
+    /// [`paths`]: #method.paths
+    fn changes_paths(&self, commit: &git2::Commit<'_>) -> Result<bool, Error> {
+        if self.paths.is_empty() {
+            return Ok(true);
+        }
+
+        let mut opts = git2::DiffOptions::new();
+
+        for pattern in &self.paths {
+            opts.pathspec(pattern);
+        }
+
+        let tree = commit.tree()?;
+        // Root commits are compared to the empty tree.
+        let parent = match commit.parent_count() {
+            0 => None,
+            _ => Some(commit.parent(0)?.tree()?),
+        };
+
+        let diff = self
+            .repo
+            .diff_tree_to_tree(parent.as_ref(), Some(&tree), Some(&mut opts))?;
+
+        Ok(diff.deltas().len() > 0)
+    }
+
+    /// Read the notes attached to the commits from a notes reference, such as `refs/notes/commits`.
+    pub fn notes(mut self, reference: &str) -> Self {
+        self.notes = Some(reference.to_string());
         self
     }
 }
 
-impl Iterator for Commits<'_> {
-    type Item = Commit;
+impl Commits<'_> {
+    /// Read a commit, resolving its author and committer through the mailmap.
+    fn read(&self, commit: &git2::Commit<'_>) -> Result<Commit, Error> {
+        let hash = commit.id().to_string();
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let oid = match self.inner.next() {
-            Some(Ok(oid)) => oid,
-            _ => return None,
+        let (author, committer) = match &self.mailmap {
+            Some(mailmap) => (
+                commit.author_with_mailmap(mailmap).ok(),
+                commit.committer_with_mailmap(mailmap).ok(),
+            ),
+            None => (None, None),
         };
 
-        let commit = match self.repo.find_commit(oid) {
-            Ok(commit) => commit,
-            Err(_) => return None,
+        let author = author.unwrap_or_else(|| commit.author().to_owned());
+        let committer = committer.unwrap_or_else(|| commit.committer().to_owned());
+
+        let utf8 = |part: Option<&str>, name| {
+            part.map(str::to_string).ok_or_else(|| Error::InvalidUtf8 {
+                hash: hash.clone(),
+                part: name,
+            })
         };
 
-        let author = commit.author();
-        let committer = commit.committer();
+        // Signatures of old commits are commonly encoded in Latin-1, which is not fatal.
+        let lossy = |bytes: &[u8], name| match String::from_utf8_lossy(bytes) {
+            Cow::Borrowed(text) => text.to_string(),
+            Cow::Owned(text) => {
+                warn!(
+                    "the {} of commit {} is not valid UTF-8: {}",
+                    name, hash, text
+                );
+                text
+            }
+        };
 
-        let commit = Commit {
-            hash: commit.id().to_string(),
+        Ok(Commit {
             author: User {
-                name: author.name().unwrap().to_string(),
-                email: author.email().unwrap().to_string(),
+                name: lossy(author.name_bytes(), "author name"),
+                email: lossy(author.email_bytes(), "author email"),
             },
             committer: User {
-                name: committer.name().unwrap().to_string(),
-                email: committer.email().unwrap().to_string(),
+                name: lossy(committer.name_bytes(), "committer name"),
+                email: lossy(committer.email_bytes(), "committer email"),
             },
-            message: commit.summary().unwrap().to_string(),
-        };
+            time: commit_time(commit)?,
+            message: utf8(commit.summary(), "message")?,
+            body: split_message(&utf8(commit.message(), "message")?).1,
+            note: self
+                .notes
+                .as_ref()
+                .and_then(|notes| self.repo.find_note(Some(notes), commit.id()).ok())
+                .and_then(|note| note.message().map(str::to_string)),
+            hash,
+        })
+    }
+}
 
-        if oid == self.end {
-            // We have reached the ending boundary. Reset the Revwalk's configuration,
-            // so that it no longers provides further commits.
-            self.inner.reset().unwrap();
-        }
+impl Iterator for Commits<'_> {
+    type Item = Result<Commit, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let oid = match self.inner.next()? {
+                Ok(oid) => oid,
+                Err(err) => return Some(Err(err.into())),
+            };
+
+            if oid == self.end {
+                // We have reached the ending boundary. Reset the Revwalk's configuration,
+                // so that it no longers provides further commits.
+                if let Err(err) = self.inner.reset() {
+                    return Some(Err(err.into()));
+                }
+            }
+
+            let commit = match self.repo.find_commit(oid) {
+                Ok(commit) => commit,
+                Err(err) => return Some(Err(err.into())),
+            };
 
-        Some(commit)
+            if self.no_merges && commit.parent_count() > 1 {
+                trace!("skipping commit {}: it is a merge commit", oid);
+                continue;
+            }
+
+            match self.changes_paths(&commit) {
+                Ok(true) => return Some(self.read(&commit)),
+                Ok(false) => trace!("skipping commit {}: it changes none of the paths", oid),
+                Err(err) => return Some(Err(err)),
+            }
+        }
     }
 }
 
@@ -102,40 +422,565 @@ impl Iterator for Commits<'_> {
 /// [`Repository`]: https://docs.rs/git2/*/git2/struct.Repository.html
 pub struct Repository {
     inner: git2::Repository,
+    /// The name of the remote whose branches and URL are used.
+    remote: String,
+    /// The kind of branches that commits are retrieved from.
+    branches: Branches,
 }
 
 impl Repository {
     /// Open a local repository at `path`.
-    pub fn open<P>(path: P) -> Result<Self>
+    pub fn open<P>(path: P) -> Result<Self, Error>
     where
         P: AsRef<Path>,
     {
         Ok(Self {
             inner: git2::Repository::open(path)?,
+            remote: "origin".to_string(),
+            branches: Branches::Any,
         })
     }
 
-    /// Returns the URL to the repository.
-    pub fn url(&self) -> Result<String> {
-        Ok(self.inner.find_remote("origin")?.url().unwrap().to_string())
+    /// Open the local repository containing `path`, searching its parent directories, so that
+    /// it may be a subdirectory of the working tree, or of a linked worktree.
+    pub fn discover<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let inner = git2::Repository::discover(path)?;
+        debug!("discovered the repository at {}", inner.path().display());
+
+        Ok(Self {
+            inner,
+            remote: "origin".to_string(),
+            branches: Branches::Any,
+        })
     }
 
-    /// Returns an iterator of [`Commit`]s from a branch.
+    /// Use the branches and URL of the remote with the `name`, instead of `origin`.
+    pub fn with_remote<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.remote = name.into();
+        self
+    }
+
+    /// Retrieve commits only from the kind of branches given, instead of local branches
+    /// and the remote-tracking branches of the remote.
+    pub fn with_branches(mut self, branches: Branches) -> Self {
+        self.branches = branches;
+        self
+    }
+
+    /// Returns the path to the `.git` directory of the repository.
+    pub fn git_dir(&self) -> &Path {
+        self.inner.path()
+    }
+
+    /// Returns the root of the working tree of the repository, or the path to the `.git`
+    /// directory if the repository is bare.
+    pub fn root(&self) -> &Path {
+        self.inner.workdir().unwrap_or_else(|| self.inner.path())
+    }
+
+    /// Returns the directory of the repository's hooks, respecting the `core.hooksPath` option.
+    pub fn hooks_dir(&self) -> Result<PathBuf, Error> {
+        let config = self.inner.config()?;
+
+        match config.get_path("core.hooksPath") {
+            Ok(path) if path.is_absolute() => Ok(path),
+            Ok(path) => Ok(self.root().join(path)),
+            Err(_) => Ok(self.inner.path().join("hooks")),
+        }
+    }
+
+    /// Set an option in the repository's local configuration.
+    pub fn set_config(&self, key: &str, value: &str) -> Result<(), Error> {
+        self.inner
+            .config()?
+            .open_level(git2::ConfigLevel::Local)?
+            .set_str(key, value)?;
+        Ok(())
+    }
+
+    /// Returns whether the history of the repository is walked with the help of a commit-graph file.
+    pub fn commit_graph(&self) -> CommitGraph {
+        use std::io::Read;
+
+        // The chunks of a commit-graph that libgit2 understands.
+        const CHUNKS: [&[u8; 4]; 6] = [b"OIDF", b"OIDL", b"CDAT", b"EDGE", b"BIDX", b"BDAT"];
+
+        let info = self.inner.path().join("objects").join("info");
+
+        let mut file = match std::fs::File::open(info.join("commit-graph")) {
+            Ok(file) => file,
+            Err(_) if info.join("commit-graphs").is_dir() => return CommitGraph::Unsupported,
+            Err(_) => return CommitGraph::Missing,
+        };
+
+        // The header consists of the signature, the version, the hash version, the number
+        // of chunks, and the number of base commit-graphs, followed by a table of chunks.
+        let mut header = [0; 8];
+
+        if file.read_exact(&mut header).is_err() || &header[..6] != b"CGPH\x01\x01" {
+            return CommitGraph::Unsupported;
+        }
+
+        let mut table = vec![0; 12 * header[6] as usize];
+
+        if file.read_exact(&mut table).is_err() {
+            return CommitGraph::Unsupported;
+        }
+
+        let supported = table
+            .chunks(12)
+            .all(|entry| CHUNKS.iter().any(|id| entry[..4] == id[..]));
+
+        if supported {
+            CommitGraph::Usable
+        } else {
+            CommitGraph::Unsupported
+        }
+    }
+
+    /// Returns the URL to the repository, as defined by its remote, in the canonical form of
+    /// [`normalize_url`], such as `https://github.com/owner/repo` for `git@github.com:owner/repo.git`.
     ///
-    /// [`Commit`]: struct.Commit.html
-    pub fn commits(&self, branch: &str) -> Result<Commits<'_>> {
-        let reference = self
+    /// [`normalize_url`]: ../release/fn.normalize_url.html
+    pub fn url(&self) -> Result<String, Error> {
+        let remote = self
+            .inner
+            .find_remote(&self.remote)
+            .map_err(|err| match err.code() {
+                git2::ErrorCode::NotFound => Error::UnknownRemote(self.remote.clone()),
+                _ => err.into(),
+            })?;
+
+        let url = remote
+            .url()
+            .ok_or_else(|| Error::InvalidRemoteUrl(self.remote.clone()))?;
+
+        info!("using the URL of the remote `{}`: {}", self.remote, url);
+
+        normalize_url(url)
+    }
+
+    /// Fetch the `branch` and the tags from the remote, updating its remote-tracking branch,
+    /// or else all branches of the remote, as by its configured refspecs.
+    ///
+    /// The remote is authenticated with the SSH agent for SSH URLs, and with the credential
+    /// helpers of Git's configuration for HTTPS URLs.
+    pub fn fetch(&self, branch: Option<&str>) -> Result<(), Error> {
+        let mut remote = self
+            .inner
+            .find_remote(&self.remote)
+            .map_err(|err| match err.code() {
+                git2::ErrorCode::NotFound => Error::UnknownRemote(self.remote.clone()),
+                _ => err.into(),
+            })?;
+
+        let refspecs = match branch {
+            Some(branch) => vec![format!(
+                "+refs/heads/{0}:refs/remotes/{1}/{0}",
+                branch, self.remote
+            )],
+            None => Vec::new(),
+        };
+
+        let config = self.inner.config()?;
+        let mut attempts = 0;
+
+        let mut callbacks = git2::RemoteCallbacks::new();
+        callbacks.credentials(|url, username, allowed| {
+            // libgit2 asks again as long as the credentials are rejected.
+            attempts += 1;
+
+            if attempts > 3 {
+                return Err(git2::Error::from_str("the remote rejected the credentials"));
+            }
+
+            if allowed.contains(git2::CredentialType::USERNAME) {
+                git2::Cred::username(username.unwrap_or("git"))
+            } else if allowed.contains(git2::CredentialType::SSH_KEY) {
+                git2::Cred::ssh_key_from_agent(username.unwrap_or("git"))
+            } else if allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
+                git2::Cred::credential_helper(&config, url, username)
+            } else {
+                git2::Cred::default()
+            }
+        });
+
+        let mut options = git2::FetchOptions::new();
+        options
+            .remote_callbacks(callbacks)
+            .download_tags(git2::AutotagOption::All);
+
+        info!("fetching from the remote `{}`", self.remote);
+
+        remote.fetch(&refspecs, Some(&mut options), None)?;
+
+        // A branch that the remote lacks is not fetched, without failing.
+        if let Some(branch) = branch {
+            let name = format!("refs/heads/{}", branch);
+
+            if !remote.list()?.iter().any(|head| head.name() == name) {
+                return Err(Error::UnknownRemoteBranch {
+                    remote: self.remote.clone(),
+                    branch: branch.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Expands an abbreviated commit hash to the full hash of the unique commit it identifies.
+    pub fn expand_hash(&self, hash: &str) -> Result<String, Error> {
+        Ok(resolve_hash(&self.inner, hash)?.to_string())
+    }
+
+    /// Returns the shortest length that abbreviates all `hashes` of commits unambiguously
+    /// among the objects of the repository.
+    ///
+    /// Hashes are abbreviated as Git does, to at least the length of its `core.abbrev` setting.
+    pub fn abbreviation_length<'a, I>(&self, hashes: I) -> Result<usize, Error>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut len = 0;
+
+        for hash in hashes {
+            let object = self.inner.find_object(git2::Oid::from_str(hash)?, None)?;
+            let short = object.short_id()?;
+            len = len.max(short.len());
+        }
+
+        Ok(len)
+    }
+
+    /// Returns the hash of the commit that the tag with the `name` points to.
+    ///
+    /// Both annotated and lightweight tags are resolved.
+    pub fn tag(&self, name: &str) -> Result<String, Error> {
+        let commit = self
             .inner
-            .find_reference(&format!("refs/remotes/origin/{}", branch))?;
+            .revparse_single(&format!("refs/tags/{}", name))
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|err| match err.code() {
+                git2::ErrorCode::NotFound => Error::UnknownTag(name.to_string()),
+                _ => Error::InvalidRevision {
+                    revision: name.to_string(),
+                    message: err.message().to_string(),
+                },
+            })?;
+
+        Ok(commit.id().to_string())
+    }
 
+    /// Returns all tags whose names match the glob `pattern`.
+    ///
+    /// Tags that do not point to a commit are skipped.
+    pub fn tags(&self, pattern: &str) -> Result<Vec<Tag>, Error> {
+        let mut tags = Vec::new();
+
+        for name in self.inner.tag_names(Some(pattern))?.iter().flatten() {
+            let object = self.inner.revparse_single(&format!("refs/tags/{}", name))?;
+
+            let commit = match object.peel_to_commit() {
+                Ok(commit) => commit,
+                Err(_) => continue,
+            };
+
+            tags.push(Tag {
+                name: name.to_string(),
+                hash: commit.id().to_string(),
+                time: commit_time(&commit)?,
+            });
+        }
+
+        Ok(tags)
+    }
+
+    /// Returns the number of commits reachable from `to`, but not from `from`.
+    ///
+    /// If `from` is absent, all commits reachable from `to` are counted.
+    pub fn count_commits(&self, from: Option<&str>, to: &str) -> Result<usize, Error> {
         let mut revwalk = self.inner.revwalk()?;
-        revwalk.push(reference.target().unwrap())?;
+        revwalk.push(git2::Oid::from_str(to)?)?;
+
+        if let Some(from) = from {
+            revwalk.hide(git2::Oid::from_str(from)?)?;
+        }
+
+        Ok(revwalk.count())
+    }
+
+    /// Returns the emails, in lowercase, of the authors of the ancestors of the commits
+    /// with the `hashes` that are not among them, resolved through the mailmap.
+    ///
+    /// These are the authors that contributed before the commits.
+    pub fn prior_authors(&self, hashes: &[String]) -> Result<HashSet<String>, Error> {
+        let mut revwalk = self.inner.revwalk()?;
+        let mut listed = HashSet::with_capacity(hashes.len());
+
+        for hash in hashes {
+            let oid = git2::Oid::from_str(hash)?;
+            revwalk.push(oid)?;
+            listed.insert(oid);
+        }
+
+        let mailmap = self.inner.mailmap().ok();
+        let mut authors = HashSet::new();
+
+        for oid in revwalk {
+            let oid = oid?;
+
+            if listed.contains(&oid) {
+                continue;
+            }
+
+            let commit = self.inner.find_commit(oid)?;
+            let author = match &mailmap {
+                Some(mailmap) => commit.author_with_mailmap(mailmap)?,
+                None => commit.author().to_owned(),
+            };
+
+            if let Some(email) = author.email() {
+                authors.insert(email.to_lowercase());
+            }
+        }
+
+        Ok(authors)
+    }
+
+    fn walk(&self, tip: git2::Oid) -> Result<Commits<'_>, Error> {
+        let mut revwalk = self.inner.revwalk()?;
+        revwalk.push(tip)?;
         revwalk.set_sorting(git2::Sort::TOPOLOGICAL)?;
 
         Ok(Commits {
             repo: &self.inner,
             inner: revwalk,
+            tip,
             end: git2::Oid::from_str("0")?,
+            notes: None,
+            mailmap: self.inner.mailmap().ok(),
+            paths: Vec::new(),
+            no_merges: false,
+        })
+    }
+
+    /// Returns an iterator of [`Commit`]s from a branch.
+    ///
+    /// By default, a local branch is preferred to the remote-tracking branch of the same name.
+    /// See [`with_branches`].
+    ///
+    /// [`Commit`]: struct.Commit.html
+    /// [`with_branches`]: #method.with_branches
+    pub fn commits(&self, branch: &str) -> Result<Commits<'_>, Error> {
+        let local = format!("refs/heads/{}", branch);
+        let tracking = format!("refs/remotes/{}/{}", self.remote, branch);
+
+        let candidates = match self.branches {
+            Branches::Any => vec![local, tracking],
+            Branches::Local => vec![local],
+            Branches::Remote => vec![tracking],
+        };
+
+        for name in &candidates {
+            match self.inner.find_reference(name) {
+                Ok(reference) => {
+                    info!("retrieving the commits of `{}`", name);
+                    return self.walk(reference.peel_to_commit()?.id());
+                }
+                Err(err) if err.code() == git2::ErrorCode::NotFound => {
+                    debug!("no branch `{}`", name);
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        if self.branches == Branches::Remote && self.inner.find_remote(&self.remote).is_err() {
+            return Err(Error::UnknownRemote(self.remote.clone()));
+        }
+
+        Err(Error::UnknownBranch {
+            remote: self.remote.clone(),
+            branch: branch.to_string(),
+            branches: self.branch_names().unwrap_or_default(),
         })
     }
+
+    /// Returns an iterator of [`Commit`]s from a revision or a range of revisions, as understood
+    /// by `git rev-parse`, such as `v1.2.0..HEAD` or `origin/main~20..origin/main`.
+    ///
+    /// A single revision lists all commits reachable from it. A symmetric difference, such as
+    /// `main...topic`, lists the commits reachable from either side, but not from both.
+    ///
+    /// [`Commit`]: struct.Commit.html
+    pub fn revisions(&self, spec: &str) -> Result<Commits<'_>, Error> {
+        let invalid = |err: git2::Error| Error::InvalidRevision {
+            revision: spec.to_string(),
+            message: err.message().to_string(),
+        };
+
+        info!("retrieving the commits of `{}`", spec);
+
+        let revspec = self.inner.revparse(spec).map_err(invalid)?;
+        let commit = |object: Option<&git2::Object<'_>>| match object {
+            Some(object) => object
+                .peel_to_commit()
+                .map(|c| Some(c.id()))
+                .map_err(invalid),
+            None => Ok(None),
+        };
+
+        let (from, to) = match (commit(revspec.from())?, commit(revspec.to())?) {
+            (Some(from), Some(to)) => (from, to),
+            (Some(tip), None) | (None, Some(tip)) => return self.walk(tip),
+            (None, None) => {
+                return Err(Error::InvalidRevision {
+                    revision: spec.to_string(),
+                    message: "the revision identifies no commit".to_string(),
+                })
+            }
+        };
+
+        let mut commits = self.walk(to)?;
+
+        if revspec.mode().contains(git2::RevparseMode::MERGE_BASE) {
+            commits.inner.push(from)?;
+            commits.inner.hide(self.inner.merge_base(from, to)?)?;
+        } else {
+            commits.inner.hide(from)?;
+        }
+
+        Ok(commits)
+    }
+
+    /// Returns the names of the branches that [`commits`] may retrieve, local branches first.
+    ///
+    /// [`commits`]: #method.commits
+    pub fn branch_names(&self) -> Result<Vec<String>, Error> {
+        let prefix = format!("{}/", self.remote);
+        let mut names: Vec<String> = Vec::new();
+
+        let kind = match self.branches {
+            Branches::Any => None,
+            Branches::Local => Some(git2::BranchType::Local),
+            Branches::Remote => Some(git2::BranchType::Remote),
+        };
+
+        for branch in self.inner.branches(kind)? {
+            let (branch, kind) = branch?;
+
+            let name = match (branch.name()?, kind) {
+                (Some(name), git2::BranchType::Local) => name,
+                (Some(name), git2::BranchType::Remote) => match name.strip_prefix(&prefix) {
+                    Some("HEAD") | None => continue,
+                    Some(name) => name,
+                },
+                (None, _) => continue,
+            };
+
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// Returns the name of the default branch.
+    ///
+    /// The default branch is the one `HEAD` of the remote points to, as recorded by `git clone`
+    /// or `git remote set-head`, unless only local branches are used. Otherwise, it is the branch
+    /// checked out locally, or else `main` or `master`, whichever exists.
+    pub fn default_branch(&self) -> Result<String, Error> {
+        let prefix = format!("refs/remotes/{}/", self.remote);
+
+        if self.branches != Branches::Local {
+            if let Ok(head) = self.inner.find_reference(&format!("{}HEAD", prefix)) {
+                if let Some(name) = head.symbolic_target().and_then(|t| t.strip_prefix(&prefix)) {
+                    info!(
+                        "the default branch is `{}`, as `HEAD` of `{}`",
+                        name, self.remote
+                    );
+                    return Ok(name.to_string());
+                }
+            }
+        }
+
+        let branches = self.branch_names()?;
+
+        if branches.is_empty()
+            && self.branches == Branches::Remote
+            && self.inner.find_remote(&self.remote).is_err()
+        {
+            return Err(Error::UnknownRemote(self.remote.clone()));
+        }
+
+        let local = self
+            .inner
+            .head()
+            .ok()
+            .filter(|head| head.is_branch())
+            .and_then(|head| head.shorthand().map(str::to_string));
+
+        let branch = local
+            .into_iter()
+            .chain(vec!["main".to_string(), "master".to_string()])
+            .find(|name| branches.contains(name));
+
+        if let Some(branch) = &branch {
+            info!("the default branch is `{}`", branch);
+        }
+
+        branch.ok_or_else(|| Error::NoDefaultBranch {
+            remote: self.remote.clone(),
+            branches,
+        })
+    }
+
+    /// Returns the commits of the branch merged by a merge commit, being those reachable
+    /// from its second parent, but not from its first parent.
+    ///
+    /// Returns no commits if the commit is not a merge commit.
+    pub fn merged_commits(&self, hash: &str) -> Result<Vec<Commit>, Error> {
+        let commit = self.inner.find_commit(git2::Oid::from_str(hash)?)?;
+
+        if commit.parent_count() < 2 {
+            return Ok(Vec::new());
+        }
+
+        let first = commit.parent_id(0)?.to_string();
+        let second = commit.parent_id(1)?.to_string();
+
+        self.range(Some(&first), &second)?.collect()
+    }
+
+    /// Returns an iterator of [`Commit`]s reachable from the `to` hash, but not from the `from` hash.
+    ///
+    /// If `from` is absent, all commits reachable from `to` are returned.
+    ///
+    /// [`Commit`]: struct.Commit.html
+    pub fn range(&self, from: Option<&str>, to: &str) -> Result<Commits<'_>, Error> {
+        let mut commits = self.walk(git2::Oid::from_str(to)?)?;
+
+        if let Some(from) = from {
+            commits.inner.hide(git2::Oid::from_str(from)?)?;
+        }
+
+        Ok(commits)
+    }
+}
+
+/// Returns the root of the working tree of the repository containing `path`, where the
+/// configuration and the stored releases are, or `path` itself if it is not in a repository.
+pub fn repo_root(path: &Path) -> PathBuf {
+    match Repository::discover(path) {
+        Ok(repo) => repo.root().to_path_buf(),
+        Err(_) => path.to_path_buf(),
+    }
 }
@@ -0,0 +1,108 @@
+use crate::github::NewRelease;
+use crate::http;
+use crate::Result;
+
+use serde::Deserialize;
+use tracing::debug;
+
+#[derive(Deserialize)]
+struct ApiRepository {
+    permissions: Option<ApiPermissions>,
+}
+
+#[derive(Deserialize)]
+struct ApiPermissions {
+    push: bool,
+}
+
+#[derive(Deserialize)]
+struct ApiRelease {
+    html_url: String,
+}
+
+/// Returns the URL of the instance and the `owner/name` of a repository from its URL,
+/// such as `https://codeberg.org` and `owner/name` of `https://codeberg.org/owner/name`.
+///
+/// As an instance may be served under a path, the repository is the last two components
+/// of the path.
+pub fn split_repo_url(url: &str) -> Option<(&str, &str)> {
+    let url = url.trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+
+    let (rest, name) = url.rsplit_once('/')?;
+    let (instance, owner) = rest.rsplit_once('/')?;
+
+    if owner.is_empty() || name.is_empty() || !instance.contains("://") {
+        return None;
+    }
+
+    Some((instance, &url[instance.len() + 1..]))
+}
+
+/// A client of the REST API of a Gitea or Forgejo instance.
+pub struct Client {
+    agent: ureq::Agent,
+    url: String,
+    token: Option<String>,
+}
+
+impl Client {
+    /// Create a new client of the instance at `url`, authenticating with the `token`
+    /// if it is present.
+    pub fn new(url: &str, token: Option<String>) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(concat!("release-maker/", env!("CARGO_PKG_VERSION")))
+                .build(),
+            url: url.trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        let url = format!("{}/api/v1{}", self.url, path);
+        debug!("{} {}", method, url);
+
+        let req = self.agent.request(method, &url);
+
+        match &self.token {
+            Some(token) => req.set("Authorization", &format!("token {}", token)),
+            None => req,
+        }
+    }
+
+    /// Check that the client may create releases in the `repo` (as `owner/name`),
+    /// and that there is no release for the `tag` yet.
+    pub fn check_release(&self, repo: &str, tag: &str) -> Result<()> {
+        if self.token.is_none() {
+            return Err("publishing requires a token".into());
+        }
+
+        let repository: ApiRepository =
+            http::call(self.request("GET", &format!("/repos/{}", repo)))
+                .map_err(|err| format!("failed to access {}: {}", repo, err))?
+                .into_json()?;
+
+        if !repository.permissions.is_some_and(|p| p.push) {
+            return Err(format!("the token may not create releases in {}", repo).into());
+        }
+
+        match http::call(self.request("GET", &format!("/repos/{}/releases/tags/{}", repo, tag))) {
+            Ok(_) => Err(format!("{} already has a release for {}", repo, tag).into()),
+            Err(err) if err.status() == Some(404) => Ok(()),
+            Err(err) => Err(format!("failed to find the release for {}: {}", tag, err).into()),
+        }
+    }
+
+    /// Create a release in the `repo` (as `owner/name`), returning the URL to it.
+    pub fn create_release(&self, repo: &str, release: &NewRelease) -> Result<String> {
+        let created: ApiRelease = http::send_json(
+            self.request("POST", &format!("/repos/{}/releases", repo)),
+            release,
+        )
+        .map_err(|err| format!("failed to create the release in {}: {}", repo, err))?
+        .into_json()?;
+
+        Ok(created.html_url)
+    }
+}
@@ -0,0 +1,585 @@
+use crate::git::{split_message, Commit, User};
+use crate::http;
+use crate::Result;
+
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use std::process::{Command, Stdio};
+
+/// The URL of GitHub itself.
+pub const WEB_URL: &str = "https://github.com";
+
+/// The URL of the GitHub REST API.
+pub const API_URL: &str = "https://api.github.com";
+
+/// The path of the REST API of a GitHub Enterprise Server, relative to the URL of the server.
+const ENTERPRISE_API_PATH: &str = "/api/v3";
+
+/// The maximum amount of items the API returns per page.
+const PER_PAGE: usize = 100;
+
+#[derive(Deserialize)]
+struct Comparison {
+    total_commits: usize,
+    commits: Vec<ApiCommit>,
+}
+
+#[derive(Deserialize)]
+struct ApiCommit {
+    sha: String,
+    commit: ApiCommitData,
+    author: Option<ApiUser>,
+    committer: Option<ApiUser>,
+}
+
+#[derive(Deserialize)]
+struct ApiCommitData {
+    author: ApiSignature,
+    committer: ApiSignature,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct ApiSignature {
+    name: String,
+    email: String,
+    date: String,
+}
+
+#[derive(Deserialize)]
+struct ApiUser {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct ApiPullRequest {
+    number: u64,
+    body: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiLabel {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct ApiRepository {
+    permissions: Option<ApiPermissions>,
+}
+
+#[derive(Deserialize)]
+struct ApiPermissions {
+    push: bool,
+}
+
+#[derive(Deserialize)]
+struct ApiRelease {
+    html_url: String,
+}
+
+/// A release to be created on GitHub, or on Gitea, whose API accepts the same fields.
+#[derive(Serialize, Debug, Clone)]
+pub struct NewRelease {
+    /// The name of the tag of the release. The tag is created if it does not exist.
+    pub tag_name: String,
+    /// The title of the release.
+    pub name: String,
+    /// The description of the release, as markdown.
+    pub body: String,
+    /// Whether the release is a draft, which is only visible to collaborators.
+    pub draft: bool,
+    /// Whether the release is marked as a pre-release.
+    pub prerelease: bool,
+}
+
+#[derive(Deserialize)]
+struct ApiCommitAccount {
+    author: Option<ApiUser>,
+}
+
+#[derive(Deserialize)]
+struct ApiReview {
+    user: Option<ApiUser>,
+    state: String,
+}
+
+/// Describes the release note of a pull request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReleaseNote {
+    /// The text of the release note.
+    Text(String),
+    /// The pull request should not be mentioned in the release, as declared by a release note of `NONE`.
+    None,
+}
+
+/// Extract the release note from the description of a pull request, contained
+/// in a fenced code block with the `release-note` info string:
+///
+/// ````markdown
+/// ```release-note
+/// Add support for stickers.
+/// ```
+/// ````
+///
+/// Lines of the release note are joined with spaces. Returns `None` if there is
+/// no release note, or if it is empty.
+pub fn release_note(body: &str) -> Option<ReleaseNote> {
+    let mut lines = body.lines();
+
+    lines.find(|line| {
+        let line = line.trim();
+        line.starts_with("```") && line.trim_start_matches('`').trim() == "release-note"
+    })?;
+
+    let text = lines
+        .take_while(|line| !line.trim_start().starts_with("```"))
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    match text.as_str() {
+        "" => None,
+        "NONE" => Some(ReleaseNote::None),
+        _ => Some(ReleaseNote::Text(text)),
+    }
+}
+
+/// Returns the login of a user from their private `users.noreply.github.com` email,
+/// such as `octocat` for `583231+octocat@users.noreply.github.com`.
+pub fn noreply_login(email: &str) -> Option<&str> {
+    Instance::default().noreply_login(email)
+}
+
+/// Returns the `owner/name` of a repository from its URL on Github,
+/// or `None` if the URL does not point to Github.
+pub fn repo_from_url(url: &str) -> Option<&str> {
+    Instance::default().repo_from_url(url)
+}
+
+/// The URLs of a GitHub instance: GitHub itself, or a GitHub Enterprise Server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instance {
+    /// The URL of the web interface, such as `https://github.example.com`.
+    pub web_url: String,
+    /// The URL of the REST API, such as `https://github.example.com/api/v3`.
+    pub api_url: String,
+}
+
+impl Default for Instance {
+    fn default() -> Self {
+        Self {
+            web_url: WEB_URL.to_string(),
+            api_url: API_URL.to_string(),
+        }
+    }
+}
+
+impl Instance {
+    /// Describe an instance by the URLs of its web interface and its API.
+    ///
+    /// If only one URL is present, the other is derived from it, as a GitHub Enterprise Server
+    /// serves its API at `/api/v3`. Without either, the instance is GitHub itself.
+    pub fn new(web_url: Option<&str>, api_url: Option<&str>) -> Self {
+        let web_url = web_url.map(|url| url.trim_end_matches('/'));
+        let api_url = api_url.map(|url| url.trim_end_matches('/'));
+
+        let (web_url, api_url) = match (web_url, api_url) {
+            (Some(web_url), Some(api_url)) => (web_url.to_string(), api_url.to_string()),
+            (Some(WEB_URL), None) | (None, Some(API_URL)) | (None, None) => return Self::default(),
+            (Some(web_url), None) => (
+                web_url.to_string(),
+                format!("{}{}", web_url, ENTERPRISE_API_PATH),
+            ),
+            (None, Some(api_url)) => (
+                api_url
+                    .strip_suffix(ENTERPRISE_API_PATH)
+                    .unwrap_or(api_url)
+                    .to_string(),
+                api_url.to_string(),
+            ),
+        };
+
+        Self { web_url, api_url }
+    }
+
+    /// Returns the host of the web interface, such as `github.com`.
+    pub fn host(&self) -> &str {
+        let url = self.web_url.as_str();
+        url.split_once("://").map_or(url, |(_, host)| host)
+    }
+
+    /// Returns the URL to a repository (as `owner/name`) of the instance.
+    pub fn repo_url(&self, repo: &str) -> String {
+        format!("{}/{}", self.web_url, repo)
+    }
+
+    /// Returns the `owner/name` of a repository from its URL on the instance,
+    /// or `None` if the URL does not point to the instance.
+    pub fn repo_from_url<'a>(&self, url: &'a str) -> Option<&'a str> {
+        let path = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))?
+            .strip_prefix(self.host())?
+            .strip_prefix('/')?;
+        let path = path.trim_end_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+
+        if path.split('/').count() == 2 {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the login of a user from their private email on the instance, such as `octocat`
+    /// for `583231+octocat@users.noreply.github.com`.
+    pub fn noreply_login<'a>(&self, email: &'a str) -> Option<&'a str> {
+        let local = email
+            .strip_suffix(self.host())?
+            .strip_suffix("@users.noreply.")?;
+        let login = local.split_once('+').map_or(local, |(_, login)| login);
+
+        if login.is_empty() {
+            None
+        } else {
+            Some(login)
+        }
+    }
+}
+
+impl ApiCommit {
+    fn into_commit(self) -> Commit {
+        // Prefer the GitHub account of a user over the name in the commit,
+        // as the name is used to mention the user.
+        fn user(sig: ApiSignature, account: Option<ApiUser>) -> User {
+            User {
+                name: account.map_or(sig.name, |account| account.login),
+                email: sig.email,
+            }
+        }
+
+        let (message, body) = split_message(&self.commit.message);
+        let time = DateTime::parse_from_rfc3339(&self.commit.committer.date).unwrap_or_default();
+
+        Commit {
+            hash: self.sha,
+            time,
+            author: user(self.commit.author, self.author),
+            committer: user(self.commit.committer, self.committer),
+            message,
+            body,
+            note: None,
+        }
+    }
+}
+
+/// Discover the token to authenticate to the GitHub API with, as the first of:
+///
+/// - the `RELEASE_MAKER_GITHUB_TOKEN`, `GITHUB_TOKEN`, or `GH_TOKEN` environment variable,
+///   or for a GitHub Enterprise Server, the `GH_ENTERPRISE_TOKEN` or `GITHUB_ENTERPRISE_TOKEN`
+///   environment variable before them,
+/// - the token the `gh` CLI is logged in to the `instance` with, as by `gh auth token`,
+/// - the `configured` token, such as of the configuration file.
+///
+/// Returns `None` if there is no token, in which case the API is used unauthenticated.
+pub fn discover_token(instance: &Instance, configured: Option<&str>) -> Option<String> {
+    let enterprise: &[&str] = if instance.host() == Instance::default().host() {
+        &[]
+    } else {
+        &["GH_ENTERPRISE_TOKEN", "GITHUB_ENTERPRISE_TOKEN"]
+    };
+
+    let vars = enterprise
+        .iter()
+        .chain(&["RELEASE_MAKER_GITHUB_TOKEN", "GITHUB_TOKEN", "GH_TOKEN"]);
+
+    for var in vars {
+        if let Some(token) = std::env::var(var).ok().filter(|token| !token.is_empty()) {
+            debug!("using the GitHub token of `{}`", var);
+            return Some(token);
+        }
+    }
+
+    let gh = Command::new("gh")
+        .args(["auth", "token", "--hostname", instance.host()])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success());
+
+    if let Some(output) = gh {
+        let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        if !token.is_empty() {
+            debug!("using the GitHub token of the `gh` CLI");
+            return Some(token);
+        }
+    }
+
+    let token = configured.filter(|token| !token.is_empty())?;
+    debug!("using the GitHub token of the configuration");
+
+    Some(token.to_string())
+}
+
+/// A client of the GitHub REST API.
+pub struct Client {
+    agent: ureq::Agent,
+    instance: Instance,
+    token: Option<String>,
+}
+
+impl Client {
+    /// Create a new client of the `instance`, authenticating with the `token` if it is present.
+    ///
+    /// Without a token, the API limits the number of requests severely.
+    pub fn new(instance: Instance, token: Option<String>) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(concat!("release-maker/", env!("CARGO_PKG_VERSION")))
+                .build(),
+            instance,
+            token,
+        }
+    }
+
+    /// Returns the instance of the client.
+    pub fn instance(&self) -> &Instance {
+        &self.instance
+    }
+
+    fn get(&self, path: &str) -> ureq::Request {
+        self.request("GET", path)
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        let url = format!("{}{}", self.instance.api_url, path);
+        debug!("{} {}", method, url);
+
+        let req = self
+            .agent
+            .request(method, &url)
+            .set("Accept", "application/vnd.github+json");
+
+        match &self.token {
+            Some(token) => req.set("Authorization", &format!("Bearer {}", token)),
+            None => req,
+        }
+    }
+
+    /// Returns the numbers of the pull requests of the `repo` (as `owner/name`) that introduced a commit.
+    pub fn pull_requests(&self, repo: &str, hash: &str) -> Result<Vec<u64>> {
+        let prs: Vec<ApiPullRequest> =
+            http::call(self.get(&format!("/repos/{}/commits/{}/pulls", repo, hash)))
+                .map_err(|err| format!("failed to find pull requests of {}: {}", hash, err))?
+                .into_json()?;
+
+        Ok(prs.into_iter().map(|pr| pr.number).collect())
+    }
+
+    /// Returns the login of the GitHub account that authored a commit of the `repo` (as `owner/name`),
+    /// or `None` if the email of the author belongs to no account.
+    pub fn author_login(&self, repo: &str, hash: &str) -> Result<Option<String>> {
+        let commit: ApiCommitAccount =
+            http::call(self.get(&format!("/repos/{}/commits/{}", repo, hash)))
+                .map_err(|err| format!("failed to find the author of {}: {}", hash, err))?
+                .into_json()?;
+
+        Ok(commit.author.map(|author| author.login))
+    }
+
+    /// Returns the description of a pull request of the `repo` (as `owner/name`), if it has one.
+    pub fn pull_request_body(&self, repo: &str, number: u64) -> Result<Option<String>> {
+        let pr: ApiPullRequest = http::call(self.get(&format!("/repos/{}/pulls/{}", repo, number)))
+            .map_err(|err| format!("failed to find #{}: {}", number, err))?
+            .into_json()?;
+
+        Ok(pr.body)
+    }
+
+    /// Returns the names of the labels of a pull request of the `repo` (as `owner/name`).
+    pub fn labels(&self, repo: &str, number: u64) -> Result<Vec<String>> {
+        let labels: Vec<ApiLabel> = http::call(
+            self.get(&format!("/repos/{}/issues/{}/labels", repo, number))
+                .query("per_page", &PER_PAGE.to_string()),
+        )
+        .map_err(|err| format!("failed to find labels of #{}: {}", number, err))?
+        .into_json()?;
+
+        Ok(labels.into_iter().map(|label| label.name).collect())
+    }
+
+    /// Returns the logins of the users that approved a pull request of the `repo` (as `owner/name`).
+    pub fn approvers(&self, repo: &str, number: u64) -> Result<Vec<String>> {
+        let reviews: Vec<ApiReview> = http::call(
+            self.get(&format!("/repos/{}/pulls/{}/reviews", repo, number))
+                .query("per_page", &PER_PAGE.to_string()),
+        )
+        .map_err(|err| format!("failed to find reviews of #{}: {}", number, err))?
+        .into_json()?;
+
+        Ok(reviews
+            .into_iter()
+            .filter(|review| review.state == "APPROVED")
+            .filter_map(|review| review.user.map(|user| user.login))
+            .collect())
+    }
+
+    /// Check that the client may create releases in the `repo` (as `owner/name`),
+    /// and that there is no release for the `tag` yet.
+    pub fn check_release(&self, repo: &str, tag: &str) -> Result<()> {
+        if self.token.is_none() {
+            return Err("publishing requires a token".into());
+        }
+
+        let repository: ApiRepository = http::call(self.get(&format!("/repos/{}", repo)))
+            .map_err(|err| format!("failed to access {}: {}", repo, err))?
+            .into_json()?;
+
+        if !repository.permissions.is_some_and(|p| p.push) {
+            return Err(format!("the token may not create releases in {}", repo).into());
+        }
+
+        match http::call(self.get(&format!("/repos/{}/releases/tags/{}", repo, tag))) {
+            Ok(_) => Err(format!("{} already has a release for {}", repo, tag).into()),
+            Err(err) if err.status() == Some(404) => Ok(()),
+            Err(err) => Err(format!("failed to find the release for {}: {}", tag, err).into()),
+        }
+    }
+
+    /// Create a release in the `repo` (as `owner/name`), returning the URL to it.
+    pub fn create_release(&self, repo: &str, release: &NewRelease) -> Result<String> {
+        let created: ApiRelease = http::send_json(
+            self.request("POST", &format!("/repos/{}/releases", repo)),
+            release,
+        )
+        .map_err(|err| format!("failed to create the release in {}: {}", repo, err))?
+        .into_json()?;
+
+        Ok(created.html_url)
+    }
+
+    /// Returns the [`Commit`]s of the `repo` (as `owner/name`) that are reachable from
+    /// the `head` revision, but not from the `base` revision, newest first.
+    ///
+    /// [`Commit`]: ../git/struct.Commit.html
+    pub fn compare(&self, repo: &str, base: &str, head: &str) -> Result<Vec<Commit>> {
+        let path = format!("/repos/{}/compare/{}...{}", repo, base, head);
+
+        let mut commits = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let comparison: Comparison = http::call(
+                self.get(&path)
+                    .query("per_page", &PER_PAGE.to_string())
+                    .query("page", &page.to_string()),
+            )
+            .map_err(|err| {
+                format!(
+                    "failed to compare `{}...{}` of {}: {}",
+                    base, head, repo, err
+                )
+            })?
+            .into_json()?;
+
+            let len = comparison.commits.len();
+            commits.extend(comparison.commits.into_iter().map(ApiCommit::into_commit));
+
+            if len < PER_PAGE || commits.len() >= comparison.total_commits {
+                break;
+            }
+
+            page += 1;
+        }
+
+        // The API lists commits in chronological order, whereas the local repository lists the newest first.
+        commits.reverse();
+
+        Ok(commits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_notes() {
+        let body = "Adds stickers.\n\n```release-note\nAdd support\n  for stickers.\n```\n";
+        assert_eq!(
+            release_note(body),
+            Some(ReleaseNote::Text("Add support for stickers.".to_string()))
+        );
+
+        assert_eq!(
+            release_note("``` release-note\nNONE\n```"),
+            Some(ReleaseNote::None)
+        );
+        assert_eq!(release_note("```release-note\n\n```"), None);
+        assert_eq!(release_note("```rust\nfn main() {}\n```"), None);
+        assert_eq!(release_note("No note."), None);
+    }
+
+    #[test]
+    fn instances() {
+        assert_eq!(Instance::new(None, None), Instance::default());
+        assert_eq!(
+            Instance::new(Some("https://github.com/"), None),
+            Instance::default()
+        );
+        assert_eq!(Instance::new(None, Some(API_URL)), Instance::default());
+
+        let enterprise = Instance {
+            web_url: "https://github.example.com".to_string(),
+            api_url: "https://github.example.com/api/v3".to_string(),
+        };
+
+        assert_eq!(
+            Instance::new(Some("https://github.example.com/"), None),
+            enterprise
+        );
+        assert_eq!(
+            Instance::new(None, Some("https://github.example.com/api/v3")),
+            enterprise
+        );
+        assert_eq!(
+            Instance::new(
+                Some("https://github.example.com"),
+                Some("https://api.example.com")
+            ),
+            Instance {
+                web_url: "https://github.example.com".to_string(),
+                api_url: "https://api.example.com".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn noreply_logins() {
+        assert_eq!(
+            noreply_login("583231+octocat@users.noreply.github.com"),
+            Some("octocat")
+        );
+        assert_eq!(
+            noreply_login("octocat@users.noreply.github.com"),
+            Some("octocat")
+        );
+        assert_eq!(noreply_login("octocat@github.com"), None);
+        assert_eq!(noreply_login("583231+@users.noreply.github.com"), None);
+
+        let enterprise = Instance::new(Some("https://github.example.com"), None);
+        assert_eq!(
+            enterprise.noreply_login("1+octocat@users.noreply.github.example.com"),
+            Some("octocat")
+        );
+        assert_eq!(
+            enterprise.noreply_login("1+octocat@users.noreply.github.com"),
+            None
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use crate::git::{Commit, User};
+use crate::http;
+use crate::Result;
+
+use chrono::DateTime;
+use serde::Deserialize;
+use tracing::debug;
+
+/// The URL of the GitLab instance hosted by GitLab itself.
+pub const URL: &str = "https://gitlab.com";
+
+#[derive(Deserialize)]
+struct Comparison {
+    commits: Vec<ApiCommit>,
+}
+
+#[derive(Deserialize)]
+struct ApiCommit {
+    id: String,
+    title: String,
+    message: String,
+    author_name: String,
+    author_email: String,
+    committer_name: String,
+    committer_email: String,
+    committed_date: String,
+}
+
+#[derive(Deserialize)]
+struct MergeRequest {
+    author: ApiUser,
+}
+
+#[derive(Deserialize)]
+struct ApiUser {
+    username: String,
+}
+
+/// A client of the GitLab REST API.
+pub struct Client {
+    agent: ureq::Agent,
+    url: String,
+    token: Option<String>,
+}
+
+impl Client {
+    /// Create a new client of the GitLab instance at `url`, authenticating with the `token`
+    /// if it is present.
+    pub fn new(url: &str, token: Option<String>) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(concat!("release-maker/", env!("CARGO_PKG_VERSION")))
+                .build(),
+            url: url.trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    /// Returns the URL to a project (as `group/name`) of the instance.
+    pub fn project_url(&self, project: &str) -> String {
+        format!("{}/{}", self.url, project)
+    }
+
+    fn get(&self, project: &str, path: &str) -> ureq::Request {
+        let url = format!(
+            "{}/api/v4/projects/{}{}",
+            self.url,
+            project.replace('/', "%2F"),
+            path
+        );
+        debug!("GET {}", url);
+
+        let req = self.agent.get(&url);
+
+        match &self.token {
+            Some(token) => req.set("PRIVATE-TOKEN", token),
+            None => req,
+        }
+    }
+
+    /// Returns the username of the author of the merge request that introduced a commit, if any.
+    fn merge_request_author(&self, project: &str, hash: &str) -> Result<Option<String>> {
+        let mrs: Vec<MergeRequest> = http::call(self.get(
+            project,
+            &format!("/repository/commits/{}/merge_requests", hash),
+        ))
+        .map_err(|err| format!("failed to find merge requests of {}: {}", hash, err))?
+        .into_json()?;
+
+        Ok(mrs.into_iter().next().map(|mr| mr.author.username))
+    }
+
+    /// Returns the [`Commit`]s of the `project` (as `group/name`) that are reachable from
+    /// the `to` revision, but not from the `from` revision, newest first.
+    ///
+    /// The author of each commit is named by the username of the author of the merge request
+    /// that introduced the commit. If there is none, the name of the commit's author is used.
+    ///
+    /// [`Commit`]: ../git/struct.Commit.html
+    pub fn compare(&self, project: &str, from: &str, to: &str) -> Result<Vec<Commit>> {
+        let comparison: Comparison = http::call(
+            self.get(project, "/repository/compare")
+                .query("from", from)
+                .query("to", to),
+        )
+        .map_err(|err| {
+            format!(
+                "failed to compare `{}...{}` of {}: {}",
+                from, to, project, err
+            )
+        })?
+        .into_json()?;
+
+        let mut commits = Vec::with_capacity(comparison.commits.len());
+
+        // The API lists commits in chronological order, whereas the local repository lists the newest first.
+        for commit in comparison.commits.into_iter().rev() {
+            let author = self.merge_request_author(project, &commit.id)?;
+
+            commits.push(Commit {
+                author: User {
+                    name: author.unwrap_or(commit.author_name),
+                    email: commit.author_email,
+                },
+                committer: User {
+                    name: commit.committer_name,
+                    email: commit.committer_email,
+                },
+                time: DateTime::parse_from_rfc3339(&commit.committed_date).unwrap_or_default(),
+                hash: commit.id,
+                message: commit.title,
+                body: crate::git::split_message(&commit.message).1,
+                note: None,
+            });
+        }
+
+        Ok(commits)
+    }
+}
@@ -0,0 +1,225 @@
+use crate::release::{self, Author, AuthorLink, Change, OneOrMore, PullRequest, Release};
+
+use std::fmt;
+
+/// The size, in pixels, at which avatars are displayed.
+const AVATAR_SIZE: u32 = 20;
+
+/// Escapes characters that are significant in HTML.
+struct Escape<'a>(&'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&#39;")?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn write_author(source: &mut dyn fmt::Write, rel: &Release, author: &Author) -> fmt::Result {
+    match rel.get_author_link(author) {
+        Some(AuthorLink::Profile(url)) => write!(
+            source,
+            "<a href=\"{}\">@{}</a>",
+            Escape(&url),
+            Escape(author.name())
+        ),
+        Some(AuthorLink::Mail(url)) => write!(
+            source,
+            "<a href=\"{}\">{}</a>",
+            Escape(&url),
+            Escape(author.name())
+        ),
+        None => write!(source, "{}", Escape(author.name())),
+    }
+}
+
+/// Write a list of changes of `part`, which is either the release `rel` or one of its components,
+/// under a header.
+fn write_list(
+    source: &mut dyn fmt::Write,
+    header: &str,
+    rel: &Release,
+    part: &Release,
+    changes: &[Change],
+) -> fmt::Result {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(source, "{}\n<ul>", header)?;
+
+    for change in changes {
+        let Change(category, name, OneOrMore(authors), OneOrMore(commits), _) = change;
+
+        let (name, pr) = PullRequest::split(name);
+
+        write!(
+            source,
+            "  <li><code>[{}]</code> {} (",
+            Escape(category),
+            Escape(name)
+        )?;
+
+        for (i, author) in authors.iter().enumerate() {
+            if i != 0 {
+                source.write_str(" ")?;
+            }
+
+            write_author(source, rel, author)?;
+        }
+
+        source.write_str(")")?;
+
+        for commit in commits {
+            write!(
+                source,
+                " <a href=\"{}\"><code>{}</code></a>",
+                Escape(&part.commit_url(commit.hash())),
+                Escape(rel.short_hash(commit))
+            )?;
+        }
+
+        if let Some(PullRequest(number)) = pr {
+            write!(
+                source,
+                " <a href=\"{}\">{}</a>",
+                Escape(&part.pull_request_url(number)),
+                part.pull_request_ref(number)
+            )?;
+        }
+
+        writeln!(source, "</li>")?;
+    }
+
+    writeln!(source, "</ul>")?;
+
+    Ok(())
+}
+
+/// Generate the output message from a [`Release`] as HTML, by writing to a source implementing
+/// [`std::fmt::Write`]
+///
+/// Contributors are displayed with their Github avatars.
+///
+/// [`Release`]: ../release/struct.Release.html
+/// [`std::fmt::Write`]: std::fmt::Write
+pub fn generate_html(source: &mut dyn fmt::Write, rel: &Release) -> fmt::Result {
+    if let Some(heading) = rel.heading() {
+        writeln!(source, "<h1>{}</h1>", Escape(&heading))?;
+    }
+
+    if let Some(intro) = &rel.intro {
+        writeln!(source, "<p>{}</p>", Escape(intro.trim_end()))?;
+    }
+
+    if let Some(summary) = &rel.summary {
+        writeln!(source, "<p>{}</p>", Escape(summary.trim_end()))?;
+    }
+
+    writeln!(
+        source,
+        "<p>{}</p>\n<ul>",
+        Escape(rel.strings.contributors())
+    )?;
+
+    let authors = rel.sorted_authors();
+
+    for author in &authors {
+        if !author.is_username() {
+            write!(source, "  <li>")?;
+            write_author(source, rel, author)?;
+            writeln!(source, "</li>")?;
+            continue;
+        }
+
+        write!(
+            source,
+            "  <li><img src=\"{3}/{0}.png?size={1}\" alt=\"@{0}\" width=\"{2}\" height=\"{2}\"> ",
+            Escape(author.name()),
+            AVATAR_SIZE * 2,
+            AVATAR_SIZE,
+            Escape(rel.instance_url())
+        )?;
+        write_author(source, rel, author)?;
+        writeln!(source, "</li>")?;
+    }
+
+    writeln!(source, "</ul>")?;
+
+    let mut reviewers = rel.reviewers.iter().collect::<Vec<_>>();
+    release::sort_authors(&mut reviewers);
+
+    if !reviewers.is_empty() {
+        writeln!(source, "<p>{}</p>\n<ul>", Escape(rel.strings.reviewers()))?;
+
+        for reviewer in &reviewers {
+            write!(source, "  <li>")?;
+            write_author(source, rel, reviewer)?;
+            writeln!(source, "</li>")?;
+        }
+
+        writeln!(source, "</ul>")?;
+    }
+
+    for section in rel.sections() {
+        let header = format!("<h3>{}</h3>", Escape(&section.heading));
+        write_list(source, &header, rel, rel, section.changes)?;
+    }
+
+    for component in &rel.components {
+        writeln!(source, "<h3>{}</h3>", Escape(&component.name))?;
+
+        let part = &component.release;
+
+        for section in rel.sections_of(part) {
+            let header = format!("<h4>{}</h4>", Escape(&section.heading));
+            write_list(source, &header, rel, part, section.changes)?;
+        }
+    }
+
+    if !rel.new_contributors.is_empty() {
+        writeln!(
+            source,
+            "<h2>{}</h2>\n<ul>",
+            Escape(rel.strings.new_contributors())
+        )?;
+
+        for contributor in &rel.new_contributors {
+            write!(source, "  <li>")?;
+            write_author(source, rel, &contributor.author)?;
+            writeln!(
+                source,
+                " {0} <a href=\"{1}\">{1}</a></li>",
+                Escape(rel.strings.first_contribution()),
+                Escape(&rel.contribution_url(contributor))
+            )?;
+        }
+
+        writeln!(source, "</ul>")?;
+    }
+
+    if let Some(epilogue) = &rel.epilogue {
+        writeln!(source, "<p>{}</p>", Escape(epilogue.trim_end()))?;
+    }
+
+    if let Some(url) = rel.full_changelog_url() {
+        writeln!(
+            source,
+            "<p><strong>{0}</strong>: <a href=\"{1}\">{1}</a></p>",
+            Escape(rel.strings.full_changelog()),
+            Escape(&url)
+        )?;
+    }
+
+    Ok(())
+}
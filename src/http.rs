@@ -0,0 +1,252 @@
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use thiserror::Error;
+use tracing::{trace, warn};
+
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The number of times a request is retried after a transient failure.
+const RETRIES: u32 = 3;
+
+/// The delay before the first retry, which is doubled for each further retry.
+const BACKOFF: Duration = Duration::from_secs(1);
+
+/// The longest time to wait for a rate limit to reset before failing instead.
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// The statuses of responses to requests that may succeed when retried.
+const TRANSIENT: &[u16] = &[500, 502, 503, 504];
+
+/// The errors of requests to APIs.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The rate limit of the API is exhausted, until the given time if it is known.
+    #[error("rate limited{}", until(.0))]
+    RateLimited(Option<DateTime<Local>>),
+    /// A request that failed otherwise.
+    #[error(transparent)]
+    Request(Box<ureq::Error>),
+}
+
+fn until(time: &Option<DateTime<Local>>) -> String {
+    match time {
+        Some(time) => format!(" until {}", time.format("%Y-%m-%d %H:%M:%S")),
+        None => String::new(),
+    }
+}
+
+impl Error {
+    /// Returns the status of the response, if the request failed with one.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Error::Request(err) => match **err {
+                ureq::Error::Status(status, _) => Some(status),
+                ureq::Error::Transport(_) => None,
+            },
+            Error::RateLimited(_) => None,
+        }
+    }
+}
+
+/// Returns a header of a response, preferring GitHub's `X-RateLimit-*` names over GitLab's
+/// `RateLimit-*` names.
+fn rate_limit_header<'a>(res: &'a ureq::Response, name: &str) -> Option<&'a str> {
+    res.header(&format!("X-RateLimit-{}", name))
+        .or_else(|| res.header(&format!("RateLimit-{}", name)))
+}
+
+/// Whether a response with the `status` was refused for exceeding a rate limit.
+///
+/// GitHub refuses requests over the limit with `403 Forbidden`, which otherwise means that
+/// the request is not permitted, so such responses only count if they say so by their headers.
+fn is_rate_limited(status: u16, res: &ureq::Response) -> bool {
+    match status {
+        429 => true,
+        403 => {
+            res.header("Retry-After").is_some()
+                || rate_limit_header(res, "Remaining").map(str::trim) == Some("0")
+        }
+        _ => false,
+    }
+}
+
+/// Returns the time when the rate limit of a response resets, by its `Retry-After` header,
+/// in seconds or as a date, or else by its `X-RateLimit-Reset` header, in seconds since
+/// the Unix epoch.
+fn reset_time(res: &ureq::Response) -> Option<SystemTime> {
+    if let Some(retry_after) = res.header("Retry-After").map(str::trim) {
+        if let Ok(secs) = retry_after.parse() {
+            return Some(SystemTime::now() + Duration::from_secs(secs));
+        }
+
+        if let Ok(date) = DateTime::parse_from_rfc2822(retry_after) {
+            return Some(date.into());
+        }
+    }
+
+    let reset = rate_limit_header(res, "Reset")?.trim().parse().ok()?;
+
+    Some(UNIX_EPOCH + Duration::from_secs(reset))
+}
+
+/// Send a request, retrying it on transient failures and when rate limited.
+///
+/// Requests that fail to connect or with a server error are retried up to three times,
+/// with a delay that doubles each time. As a server error or a broken connection may occur
+/// after a request took effect, requests that are not idempotent, such as `POST` requests
+/// creating releases, are only retried if they failed to connect.
+///
+/// Requests over the rate limit of the API are retried when the limit resets, as given by the
+/// `Retry-After` or `X-RateLimit-Reset` header of the response, unless that is more than
+/// a minute away, in which case [`Error::RateLimited`] is returned.
+///
+/// [`Error::RateLimited`]: enum.Error.html#variant.RateLimited
+pub fn call(req: ureq::Request) -> Result<ureq::Response, Error> {
+    send(req, |req| req.call().map_err(Box::new))
+}
+
+/// Send a request with a JSON body, retrying it as by [`call`].
+///
+/// [`call`]: fn.call.html
+pub fn send_json(req: ureq::Request, data: impl Serialize) -> Result<ureq::Response, Error> {
+    send(req, |req| req.send_json(&data).map_err(Box::new))
+}
+
+fn send<F>(req: ureq::Request, send: F) -> Result<ureq::Response, Error>
+where
+    F: Fn(ureq::Request) -> Result<ureq::Response, Box<ureq::Error>>,
+{
+    let idempotent = is_idempotent(req.method());
+    let mut attempt = 0;
+    let mut backoff = BACKOFF;
+
+    loop {
+        let err = match send(req.clone()) {
+            Ok(res) => {
+                if let Some(remaining) = rate_limit_header(&res, "Remaining") {
+                    trace!("{} requests remain before the rate limit", remaining);
+                }
+
+                return Ok(res);
+            }
+            Err(err) => err,
+        };
+
+        let retry = attempt < RETRIES;
+
+        let wait = match &*err {
+            ureq::Error::Status(status, res) if is_rate_limited(*status, res) => {
+                match reset_time(res) {
+                    Some(reset) => {
+                        let wait = reset.duration_since(SystemTime::now()).unwrap_or_default();
+
+                        if !retry || wait > MAX_WAIT {
+                            return Err(Error::RateLimited(Some(reset.into())));
+                        }
+
+                        wait
+                    }
+                    None if retry => backoff,
+                    None => return Err(Error::RateLimited(None)),
+                }
+            }
+            ureq::Error::Status(status, _) if retry && idempotent && TRANSIENT.contains(status) => {
+                backoff
+            }
+            ureq::Error::Transport(transport)
+                if retry && is_transient(transport.kind(), idempotent) =>
+            {
+                backoff
+            }
+            _ => return Err(Error::Request(err)),
+        };
+
+        warn!("{}; retrying in {}s", err, wait.as_secs_f32());
+
+        thread::sleep(wait);
+
+        attempt += 1;
+        backoff *= 2;
+    }
+}
+
+/// Whether a request with the `method` has the same effect when it is sent repeatedly.
+fn is_idempotent(method: &str) -> bool {
+    matches!(method, "GET" | "HEAD" | "OPTIONS" | "PUT" | "DELETE")
+}
+
+/// Whether a request that failed to be sent with the error `kind` may succeed when retried.
+///
+/// Requests that failed to connect never reached the server, whereas other failures
+/// of the connection may occur after the server received the request, so they are only
+/// transient for idempotent requests.
+fn is_transient(kind: ureq::ErrorKind, idempotent: bool) -> bool {
+    match kind {
+        ureq::ErrorKind::Dns | ureq::ErrorKind::ConnectionFailed => true,
+        ureq::ErrorKind::Io => idempotent,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: &str, headers: &str) -> ureq::Response {
+        format!("HTTP/1.1 {}\r\n{}\r\n", status, headers)
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn rate_limited() {
+        assert!(is_rate_limited(429, &response("429 Too Many Requests", "")));
+        assert!(is_rate_limited(
+            403,
+            &response("403 Forbidden", "X-RateLimit-Remaining: 0\r\n")
+        ));
+        assert!(is_rate_limited(
+            403,
+            &response("403 Forbidden", "Retry-After: 30\r\n")
+        ));
+        assert!(!is_rate_limited(403, &response("403 Forbidden", "")));
+        assert!(!is_rate_limited(
+            403,
+            &response("403 Forbidden", "X-RateLimit-Remaining: 12\r\n")
+        ));
+    }
+
+    #[test]
+    fn reset() {
+        let res = response("429 Too Many Requests", "Retry-After: 30\r\n");
+        let wait = reset_time(&res)
+            .unwrap()
+            .duration_since(SystemTime::now())
+            .unwrap();
+        assert!(wait > Duration::from_secs(25) && wait <= Duration::from_secs(30));
+
+        let res = response(
+            "429 Too Many Requests",
+            "Retry-After: Wed, 21 Oct 2015 07:28:00 GMT\r\n",
+        );
+        assert_eq!(
+            reset_time(&res),
+            Some(UNIX_EPOCH + Duration::from_secs(1445412480))
+        );
+
+        let res = response("403 Forbidden", "X-RateLimit-Reset: 1714559400\r\n");
+        assert_eq!(
+            reset_time(&res),
+            Some(UNIX_EPOCH + Duration::from_secs(1714559400))
+        );
+
+        let res = response("429 Too Many Requests", "RateLimit-Reset: 1714559400\r\n");
+        assert_eq!(
+            reset_time(&res),
+            Some(UNIX_EPOCH + Duration::from_secs(1714559400))
+        );
+
+        assert_eq!(reset_time(&response("429 Too Many Requests", "")), None);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::release::{Change, PullRequest, Release};
+
+use std::fmt;
+
+/// A version of a changelog in the format of [Keep a Changelog].
+///
+/// [Keep a Changelog]: https://keepachangelog.com
+#[derive(Debug, Clone)]
+pub struct Version<'a> {
+    /// The name of the version, such as `v1.2.3`, or `None` for unreleased changes.
+    pub name: Option<&'a str>,
+    /// The date of the release, such as `2024-05-01`, if known.
+    pub date: Option<String>,
+    /// The changes of the version.
+    pub release: &'a Release,
+}
+
+fn write_list(
+    source: &mut dyn fmt::Write,
+    header: &str,
+    part: &Release,
+    changes: &[Change],
+) -> fmt::Result {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(source, "{}\n", header)?;
+
+    for Change(category, name, ..) in changes {
+        let (name, pr) = PullRequest::split(name);
+
+        write!(
+            source,
+            "- [{}] {}",
+            part.markdown_text(category),
+            part.markdown_text(name)
+        )?;
+
+        if let Some(PullRequest(number)) = pr {
+            write!(
+                source,
+                " ([{}]({}))",
+                part.pull_request_ref(number),
+                part.pull_request_url(number)
+            )?;
+        }
+
+        writeln!(source)?;
+    }
+
+    writeln!(source)
+}
+
+/// Write the sections of `part`, which is either the release `rel` or one of its components.
+fn write_sections(
+    source: &mut dyn fmt::Write,
+    level: &str,
+    rel: &Release,
+    part: &Release,
+) -> fmt::Result {
+    for section in rel.sections_of(part) {
+        let header = format!("{} {}", level, section.heading);
+        write_list(source, &header, part, section.changes)?;
+    }
+
+    Ok(())
+}
+
+/// Write the heading and the sections of a version, without its link.
+pub fn write_version(source: &mut dyn fmt::Write, version: &Version<'_>) -> fmt::Result {
+    let rel = version.release;
+
+    match (version.name, &version.date) {
+        (Some(name), Some(date)) => writeln!(source, "## [{}] - {}\n", name, date)?,
+        (Some(name), None) => writeln!(source, "## [{}]\n", name)?,
+        (None, _) => writeln!(source, "## [Unreleased]\n")?,
+    }
+
+    write_sections(source, "###", rel, rel)?;
+
+    for component in &rel.components {
+        writeln!(source, "### {}\n", component.name)?;
+        write_sections(source, "####", rel, &component.release)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the label and the URL of the link of a version, given the name of the previous version.
+///
+/// A version links to the comparison with the previous version, or to its tag if it is the first.
+/// Unreleased changes link to the comparison of the previous version with `HEAD`, and do not link
+/// anywhere if there is no previous version.
+pub fn link(rel: &Release, name: Option<&str>, previous: Option<&str>) -> Option<(String, String)> {
+    match (name, previous) {
+        (Some(name), Some(previous)) => Some((name.to_string(), rel.compare_url(previous, name))),
+        (Some(name), None) => Some((name.to_string(), rel.tag_url(name))),
+        (None, Some(previous)) => {
+            Some(("Unreleased".to_string(), rel.compare_url(previous, "HEAD")))
+        }
+        (None, None) => None,
+    }
+}
+
+/// Generate a changelog in the format of [Keep a Changelog] from versions, newest first,
+/// by writing to a source implementing [`std::fmt::Write`].
+///
+/// Each version is linked to the comparison with the previous version at the bottom.
+///
+/// [Keep a Changelog]: https://keepachangelog.com
+/// [`std::fmt::Write`]: std::fmt::Write
+pub fn generate_keep_a_changelog(
+    source: &mut dyn fmt::Write,
+    versions: &[Version<'_>],
+) -> fmt::Result {
+    for version in versions {
+        write_version(source, version)?;
+    }
+
+    for (i, version) in versions.iter().enumerate() {
+        let previous = versions
+            .get(i + 1)
+            .and_then(|v| v.name)
+            .or(version.release.previous_version.as_deref());
+
+        if let Some((label, url)) = link(version.release, version.name, previous) {
+            writeln!(source, "[{}]: {}", label, url)?;
+        }
+    }
+
+    Ok(())
+}
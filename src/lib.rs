@@ -0,0 +1,136 @@
+//! Generate changelogs for releases in the style of [Serenity], from a JSON description
+//! of a release or from the commits of a Git repository.
+//!
+//! A [`Release`] is usually deserialized from JSON, or built from the [`Commits`] of
+//! a [`Repository`], and rendered as markdown by [`generate_msg`], or streamed to a file or
+//! standard output by [`generate_msg_io`]:
+//!
+//! ```no_run
+//! use release_maker::git::Repository;
+//! use release_maker::release::{generate_msg, Author, Change, Release, Section};
+//!
+//! # fn main() -> release_maker::Result<()> {
+//! let repo = Repository::open(".")?;
+//!
+//! let mut release = Release {
+//!     repo_url: repo.url()?,
+//!     ..Default::default()
+//! };
+//!
+//! for commit in repo.commits("master")?.start("8f3c2a1")? {
+//!     let commit = commit?;
+//!     let author = Author::from_identity(&commit.author.name, &commit.author.email);
+//!     let change = Change::new("any", commit.message, author, commit.hash)?;
+//!     release.section_mut(Section::Added).push(change);
+//! }
+//!
+//! let mut msg = String::new();
+//! generate_msg(&mut msg, &release)?;
+//! print!("{}", msg);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [Serenity]: https://github.com/serenity-rs/serenity
+//! [`Release`]: release/struct.Release.html
+//! [`Commits`]: git/struct.Commits.html
+//! [`Repository`]: git/struct.Repository.html
+//! [`generate_msg`]: release/fn.generate_msg.html
+//! [`generate_msg_io`]: release/fn.generate_msg_io.html
+
+#![deny(rust_2018_idioms)]
+
+pub mod approxidate;
+pub mod bump;
+pub mod cache;
+pub mod categorize;
+pub mod changelog;
+pub mod config;
+pub mod csv;
+pub mod discord;
+pub mod error;
+pub mod git;
+pub mod gitea;
+pub mod github;
+pub mod gitlab;
+pub mod html;
+pub mod http;
+pub mod keep_a_changelog;
+pub mod locale;
+pub mod placeholders;
+pub mod preview;
+pub mod release;
+pub mod render;
+pub mod report;
+pub mod resolved;
+pub mod retrieve;
+pub mod slack;
+pub mod store;
+pub mod style;
+pub mod template;
+pub mod text;
+pub mod validate;
+
+use clap::ArgEnum;
+use serde::Deserialize;
+
+/// The result type of the crate, with a boxed error.
+pub type Result<T, E = Box<dyn std::error::Error>> = std::result::Result<T, E>;
+
+/// The format of the generated output.
+#[derive(ArgEnum, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Format {
+    /// Markdown, as used by Github releases.
+    Markdown,
+    /// HTML, including the avatars of contributors.
+    Html,
+    /// Comma-separated values, one row per change, for spreadsheets and issue trackers.
+    Csv,
+    /// Tab-separated values, one row per change.
+    Tsv,
+    /// JSON of the release after all processing, with authors resolved and links
+    /// expanded into absolute URLs. Also accepted as `json`.
+    #[clap(alias = "json")]
+    #[serde(alias = "json")]
+    JsonResolved,
+    /// Markdown in the format of Keep a Changelog, with a heading per version
+    /// and links to the comparisons between versions.
+    KeepAChangelog,
+    /// Plain text without markup, with the URLs of commits on their own lines, for emails.
+    Text,
+    /// Markdown for Discord, with inline links, split into messages within its length limit.
+    Discord,
+    /// Slack's mrkdwn, as posted via incoming webhooks.
+    Slack,
+}
+
+impl Format {
+    /// The extension of files of the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Markdown | Format::KeepAChangelog | Format::Discord => "md",
+            Format::Html => "html",
+            Format::Slack => "txt",
+            Format::Csv => "csv",
+            Format::Tsv => "tsv",
+            Format::JsonResolved => "json",
+            Format::Text => "txt",
+        }
+    }
+
+    /// The separator of the values of tabular formats.
+    pub fn separator(self) -> Option<char> {
+        match self {
+            Format::Csv => Some(','),
+            Format::Tsv => Some('\t'),
+            Format::Markdown
+            | Format::Html
+            | Format::JsonResolved
+            | Format::KeepAChangelog
+            | Format::Text
+            | Format::Discord
+            | Format::Slack => None,
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::release::Section;
+
+use clap::ArgEnum;
+use serde::{Deserialize, Serialize};
+
+/// A language of the generated output.
+#[derive(ArgEnum, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    /// English, the default.
+    En,
+    /// German.
+    De,
+}
+
+impl Language {
+    /// The heading of a section in the language.
+    pub fn heading(self, section: Section) -> &'static str {
+        match self {
+            Language::En => section.heading(),
+            Language::De => match section {
+                Section::Breaking => "Inkompatible Änderungen",
+                Section::Added => "Hinzugefügt",
+                Section::Changed => "Geändert",
+                Section::Deprecated => "Veraltet",
+                Section::Fixed => "Behoben",
+                Section::Removed => "Entfernt",
+                Section::Security => "Sicherheit",
+            },
+        }
+    }
+
+    /// The texts of the output in the language.
+    pub fn strings(self) -> Strings {
+        let strings: [&str; 5] = match self {
+            Language::En => [
+                CONTRIBUTORS,
+                REVIEWERS,
+                NEW_CONTRIBUTORS,
+                FIRST_CONTRIBUTION,
+                FULL_CHANGELOG,
+            ],
+            Language::De => [
+                "Danke an die folgenden Personen für ihre Beiträge:",
+                "Danke an die folgenden Personen für ihre Reviews:",
+                "Neue Mitwirkende",
+                "hat erstmals beigetragen mit",
+                "Vollständiges Änderungsprotokoll",
+            ],
+        };
+
+        let [contributors, reviewers, new_contributors, first_contribution, full_changelog] =
+            strings.map(|s| Some(s.to_string()));
+
+        Strings {
+            contributors,
+            reviewers,
+            new_contributors,
+            first_contribution,
+            full_changelog,
+        }
+    }
+}
+
+const CONTRIBUTORS: &str = "Thanks to the following for their contributions:";
+const REVIEWERS: &str = "Thanks to the following for their reviews:";
+const NEW_CONTRIBUTORS: &str = "New Contributors";
+const FIRST_CONTRIBUTION: &str = "made their first contribution in";
+const FULL_CHANGELOG: &str = "Full Changelog";
+
+/// The texts of the output besides the headings of sections, such as for translating them.
+///
+/// Absent texts are written in English.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Strings {
+    /// The line above the list of contributors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contributors: Option<String>,
+    /// The line above the list of reviewers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewers: Option<String>,
+    /// The heading of the list of new contributors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_contributors: Option<String>,
+    /// The text between a new contributor and the link to their first contribution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_contribution: Option<String>,
+    /// The label of the link to the comparison with the previous release.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_changelog: Option<String>,
+}
+
+impl Strings {
+    /// Returns whether none of the texts are defined.
+    pub fn is_empty(&self) -> bool {
+        *self == Strings::default()
+    }
+
+    /// Fall back to the texts of `defaults` for texts absent from these.
+    pub fn or(self, defaults: Strings) -> Strings {
+        Strings {
+            contributors: self.contributors.or(defaults.contributors),
+            reviewers: self.reviewers.or(defaults.reviewers),
+            new_contributors: self.new_contributors.or(defaults.new_contributors),
+            first_contribution: self.first_contribution.or(defaults.first_contribution),
+            full_changelog: self.full_changelog.or(defaults.full_changelog),
+        }
+    }
+
+    pub fn contributors(&self) -> &str {
+        self.contributors.as_deref().unwrap_or(CONTRIBUTORS)
+    }
+
+    pub fn reviewers(&self) -> &str {
+        self.reviewers.as_deref().unwrap_or(REVIEWERS)
+    }
+
+    pub fn new_contributors(&self) -> &str {
+        self.new_contributors.as_deref().unwrap_or(NEW_CONTRIBUTORS)
+    }
+
+    pub fn first_contribution(&self) -> &str {
+        self.first_contribution
+            .as_deref()
+            .unwrap_or(FIRST_CONTRIBUTION)
+    }
+
+    pub fn full_changelog(&self) -> &str {
+        self.full_changelog.as_deref().unwrap_or(FULL_CHANGELOG)
+    }
+}
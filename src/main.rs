@@ -1,63 +1,367 @@
 #![deny(rust_2018_idioms)]
 
-mod git;
-mod release;
+use release_maker::cache::Cache;
+use release_maker::categorize;
+use release_maker::config::{Config, Profile};
+use release_maker::git::{repo_root, Repository};
+use release_maker::locale::Language;
+use release_maker::placeholders::{parse_var, Placeholders};
+use release_maker::preview;
+use release_maker::release::{normalize_url, AuthorFallback, Forge};
+use release_maker::render::{
+    is_stdin, read_releases, watch, write_file, write_json, Input, InputFormat, Renderer,
+};
+use release_maker::retrieve::{self, generate_release};
+use release_maker::store::{self, Store};
+use release_maker::validate;
+use release_maker::{
+    approxidate, bump, changelog, csv, gitea, github, gitlab, keep_a_changelog, report, slack,
+    Format, Result,
+};
 
-use git::{Commit, Repository};
-use release::{generate_msg, Change, Release};
-
-use clap::Parser;
+use clap::{ArgEnum, ArgGroup, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
 use serde_json::to_string_pretty;
+use tracing_subscriber::EnvFilter;
 
 use std::fs::File;
-use std::path::PathBuf;
-
-type Result<T, E = Box<dyn std::error::Error>> = std::result::Result<T, E>;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 
 static EXPLANATION: &str = include_str!("../texts/explanation.txt");
 static EXAMPLE: &str = include_str!("../texts/example.json");
 static GOTCHAS: &str = include_str!("../texts/gotchas.txt");
+static COMMIT_MSG_HOOK: &str = include_str!("../texts/commit-msg.sh");
+static PREPARE_COMMIT_MSG_HOOK: &str = include_str!("../texts/prepare-commit-msg.sh");
+static COMMIT_TEMPLATE: &str = include_str!("../texts/commit-template.txt");
+
+/// A line identifying files installed by the `install-hooks` subcommand.
+static HOOK_MARKER: &str = "# Installed by release-maker.";
+
+/// The number of lines of a file, or of the body of a release, printed by a dry run.
+const DRY_RUN_LINES: usize = 20;
 
 /// A utility tool to quickly create changelogs for Github releases.
 #[derive(Parser)]
 #[clap(name = "release-maker", version = "0.2.0")]
+struct Cli {
+    /// Log what is being done to standard error: the chosen remotes and branches with `-v`,
+    /// also skipped commits and requests to APIs with `-vv`, and everything with `-vvv`.
+    ///
+    /// `RUST_LOG` takes precedence, such as `RUST_LOG=release_maker::git=trace`.
+    #[clap(short, long, global = true, parse(from_occurrences))]
+    verbose: u8,
+    /// Print what would be written, posted, or published, without writing any files, saving
+    /// caches, or posting or publishing anything. Publishing still checks that the token may
+    /// create the release.
+    #[clap(long, global = true)]
+    dry_run: bool,
+    #[clap(subcommand)]
+    app: App,
+}
+
+#[derive(Subcommand)]
 enum App {
-    Retrieve(Retrieve),
-    Generate(Generate),
+    Retrieve(Box<Retrieve>),
+    Generate(Box<Generate>),
+    Save(Save),
+    History(History),
+    Show(Show),
+    Latest(Latest),
+    InstallHooks(InstallHooks),
+    Report(Report),
+    Publish(Publish),
+    Changelog(Changelog),
+    Version(NextVersion),
+    Run(Box<Run>),
+    Lint(Lint),
+    Completions(Completions),
 }
 
 /// Retrieve a list of Git commits from a repository's branch into json that
 /// can be plugged into the `generate` subcommand.
 #[derive(Parser)]
 #[clap(version = "0.2.0")]
+#[clap(group = ArgGroup::new("api").args(&["github", "gitlab"]))]
 struct Retrieve {
     /// Path to directory of a Git repository.
     #[clap(parse(from_os_str), default_value = ".")]
     path: PathBuf,
+    /// A revision or a range of revisions to retrieve the commits of, instead of a branch,
+    /// such as `v1.2.0..HEAD` or `origin/main~20..origin/main`.
+    ///
+    /// Accepts any revision range understood by `git rev-parse`.
+    #[clap(
+        value_name = "RANGE",
+        conflicts_with_all = &["api", "start", "to-tag", "branch"]
+    )]
+    range: Option<String>,
     /// The branch to retrieve the list of commits from.
     ///
-    /// Defaults to `master` if left undefined.
-    #[clap(short, long, default_value = "master")]
-    branch: String,
-    /// A commit hash to define the start boundary of the list.
+    /// Defaults to the branch of the `[retrieve]` table of the configuration,
+    /// or the default branch of the remote if left undefined.
+    #[clap(short, long, env = "RELEASE_MAKER_BRANCH")]
+    branch: Option<String>,
+    /// The remote whose branch is retrieved, and whose URL is the repository's.
+    ///
+    /// Defaults to the remote of the `[retrieve]` table of the configuration,
+    /// or `origin` if left undefined.
+    #[clap(long, env = "RELEASE_MAKER_REMOTE")]
+    remote: Option<String>,
+    /// The URL to the repository, such as `https://github.com/owner/repo`, for repositories
+    /// without the remote, such as fresh or mirrored ones.
+    ///
+    /// Defaults to the `repo_url` of the `[retrieve]` table of the configuration,
+    /// or the URL of the remote if left undefined.
+    #[clap(
+        long,
+        value_name = "URL",
+        env = "RELEASE_MAKER_REPO_URL",
+        conflicts_with = "api"
+    )]
+    repo_url: Option<String>,
+    /// Path to the file where the output is written.
+    ///
+    /// If the path is absent, the output will be printed to standard output instead.
+    #[clap(short, long, parse(from_os_str))]
+    output: Option<PathBuf>,
+    /// Overwrite the output file if it already exists.
+    #[clap(long, requires = "output")]
+    force: bool,
+    /// Only retrieve from local branches (`refs/heads/<branch>`).
+    ///
+    /// By default, a local branch is preferred to the remote-tracking branch of the same name.
+    #[clap(long, conflicts_with_all = &["api", "tracking"])]
+    local: bool,
+    /// Only retrieve from the remote-tracking branches of the remote (`refs/remotes/<remote>/<branch>`).
+    #[clap(long, conflicts_with = "api")]
+    tracking: bool,
+    /// Fetch the branch and the tags from the remote before retrieving the commits, so that
+    /// its remote-tracking branch is up to date, and retrieve the commits of the remote-tracking
+    /// branch, as with `--tracking`.
+    ///
+    /// Without a branch, all branches of the remote are fetched. The remote is authenticated
+    /// with the SSH agent, or the credential helpers of Git's configuration.
+    #[clap(long, conflicts_with_all = &["api", "local"])]
+    fetch: bool,
+    /// A commit hash, which may be abbreviated, to define the start boundary of the list.
     #[clap(short, long)]
     start: Option<String>,
-    /// A commit hash to define the (inclusive) end boundary of the list.
+    /// A commit hash, which may be abbreviated, to define the (inclusive) end boundary
+    /// of the list.
     ///
     /// If left undefined, this will retrieve ALL commits from the start of the list.
     #[clap(short, long)]
     end: Option<String>,
+    /// Exclude the commits reachable from a revision, such as a branch, a tag, or a hash.
+    ///
+    /// May be given multiple times, such as to omit a merged branch of imported code.
+    #[clap(short = 'x', long, value_name = "REV", conflicts_with = "api")]
+    exclude: Vec<String>,
+    /// Exclude the commits of bots, such as `dependabot[bot]` and `renovate[bot]`.
+    ///
+    /// Further bots may be defined by the `bots` patterns of the `[retrieve]` table of the configuration.
+    #[clap(long)]
+    exclude_bots: bool,
+    /// Only retrieve commits that change a path matching a pattern, such as `src/gateway`
+    /// or `*.md`, to retrieve the changes of a single component of a larger repository.
+    ///
+    /// May be given multiple times.
+    #[clap(long = "path", value_name = "PATTERN", conflicts_with = "api")]
+    paths: Vec<String>,
+    /// Skip merge commits, such as `Merge branch 'main'`.
+    #[clap(long, conflicts_with = "api")]
+    no_merges: bool,
+    /// Only follow the first parent of merge commits, skipping the commits of merged branches.
+    #[clap(long, conflicts_with = "api")]
+    first_parent: bool,
+    /// Create one change per merged pull request, rather than per commit.
+    ///
+    /// Pull requests are recognized by their merge commits, such as `Merge pull request #123 from ...`,
+    /// whose title becomes the name of the change and whose merged commits are attached to it,
+    /// and by the squashed commits that reference them, such as `Add a feature (#123)`.
+    #[clap(long, conflicts_with = "api")]
+    prs: bool,
+    /// Name the authors of commits by the logins of their GitHub accounts, so that they
+    /// link to their profiles.
+    ///
+    /// Logins are read from `users.noreply.github.com` emails, or else retrieved via the GitHub API.
+    /// Only available for repositories on Github. The API is authenticated as with `--github`.
+    #[clap(long, conflicts_with = "api")]
+    github_logins: bool,
+    /// List the authors whose first commit in the repository is among the retrieved commits
+    /// in a "New Contributors" section, along with their first commit or pull request.
+    ///
+    /// Walks the whole history before the commits.
+    #[clap(long, conflicts_with = "api")]
+    new_contributors: bool,
+    /// A tag, such as `v0.4.0`, whose commits are excluded, to retrieve the commits since it.
+    #[clap(long, value_name = "TAG", conflicts_with_all = &["api", "end"])]
+    from_tag: Option<String>,
+    /// Retrieve the commits since the most recent tag reachable from the branch,
+    /// or from the start of the list.
+    ///
+    /// If there is no such tag, all commits are retrieved.
+    #[clap(long, conflicts_with_all = &["api", "end", "from-tag"])]
+    since_last_tag: bool,
+    /// A tag, such as `v0.5.0`, to retrieve the commits up to, instead of the whole branch.
+    #[clap(long, value_name = "TAG", conflicts_with_all = &["api", "start"])]
+    to_tag: Option<String>,
+    /// Retrieve the commits of a GitHub repository, as `owner/name`, via the GitHub API
+    /// instead of a local repository.
+    ///
+    /// The API is authenticated with the first token found in the `RELEASE_MAKER_GITHUB_TOKEN`,
+    /// `GITHUB_TOKEN`, or `GH_TOKEN` environment variable, the `gh` CLI's login, or the `token`
+    /// of the `[github]` table of the configuration, if any.
+    #[clap(
+        long,
+        visible_alias = "via-api",
+        value_name = "OWNER/NAME",
+        requires = "from"
+    )]
+    github: Option<String>,
+    /// The URL of a GitHub Enterprise Server, such as `https://github.example.com`, to use
+    /// instead of GitHub itself with `--github` and the other features that use the GitHub API.
+    #[clap(long, value_name = "URL")]
+    web_url: Option<String>,
+    /// The URL of the REST API of a GitHub Enterprise Server.
+    ///
+    /// Defaults to `/api/v3` of `--web-url`.
+    #[clap(long, value_name = "URL")]
+    api_url: Option<String>,
+    /// Retrieve the commits of a GitLab project, as `group/name`, via the GitLab API
+    /// instead of a local repository.
+    ///
+    /// The `RELEASE_MAKER_GITLAB_TOKEN` or `GITLAB_TOKEN` environment variable is used to authenticate, if it is set.
+    #[clap(long, value_name = "GROUP/NAME", requires = "from")]
+    gitlab: Option<String>,
+    /// The URL of the GitLab instance, for self-hosted instances.
+    #[clap(long, value_name = "URL", env = "RELEASE_MAKER_GITLAB_URL", default_value = gitlab::URL)]
+    gitlab_url: String,
+    /// The revision, such as a tag, to define the (exclusive) start of the range of commits
+    /// retrieved via the GitHub or GitLab API.
+    #[clap(long, requires = "api")]
+    from: Option<String>,
+    /// The revision, such as a tag, to define the end of the range of commits retrieved
+    /// via the GitHub or GitLab API.
+    ///
+    /// Defaults to the branch if left undefined.
+    #[clap(long, requires = "api")]
+    to: Option<String>,
+    /// Credit the users that approved the pull requests of the changes as reviewers,
+    /// via the GitHub API.
+    ///
+    /// Only available for repositories on Github. The API is authenticated as with `--github`.
+    #[clap(long)]
+    reviewers: bool,
+    /// Categorize the changes by the labels of their pull requests, via the GitHub API.
+    ///
+    /// The mapping of labels to sections is defined in the `[labels]` table of the configuration.
+    /// Only available for repositories on Github. The API is authenticated as with `--github`.
+    #[clap(long)]
+    labels: bool,
+    /// Name the changes by the release notes in the descriptions of their pull requests,
+    /// via the GitHub API.
+    ///
+    /// A release note is contained in a fenced code block with the `release-note` info string.
+    /// Changes whose release note is `NONE` are excluded. Only available for repositories
+    /// on Github. The API is authenticated as with `--github`.
+    #[clap(long)]
+    release_notes: bool,
+    /// The maximum number of concurrent requests to the GitHub API, when resolving logins,
+    /// retrieving release notes, categorizing by labels, or retrieving reviewers.
+    #[clap(short = 'j', long, value_name = "N", default_value = "8")]
+    jobs: usize,
+    /// Path to the configuration file.
+    ///
+    /// Defaults to `.release-maker.toml` in the root of the repository, if it exists.
+    #[clap(short, long, parse(from_os_str), env = "RELEASE_MAKER_CONFIG")]
+    config: Option<PathBuf>,
+    /// The notes reference to read overrides of the categorization of commits from.
+    ///
+    /// A note consists of `key: value` lines, where the key is `section` (`breaking`, `added`,
+    /// `changed`, `deprecated`, `fixed`, `removed`, `security`, or `none` to exclude the commit),
+    /// `category`, or `name`.
+    #[clap(long, value_name = "REF", env = "RELEASE_MAKER_NOTES_REF", default_value = categorize::NOTES_REF)]
+    notes_ref: String,
+    /// Only retrieve commits committed at or after a point in time, such as `2 weeks ago`,
+    /// `yesterday`, `last month`, or `2024-05-01`.
+    #[clap(long, value_name = "TIME", parse(try_from_str = approxidate::parse))]
+    since: Option<chrono::DateTime<chrono::FixedOffset>>,
+    /// Only retrieve commits committed before a point in time, in the same format as `--since`.
+    #[clap(long, value_name = "TIME", parse(try_from_str = approxidate::parse))]
+    until: Option<chrono::DateTime<chrono::FixedOffset>>,
+    /// Do not read or write the cache of commits in `.git/release-maker-cache`, nor the cache
+    /// of GitHub API lookups in `~/.cache/release-maker`.
+    ///
+    /// The caches hold the categorization of commits, the pull requests that introduced them,
+    /// and the logins of their authors, so that repeated runs only process new commits.
+    #[clap(long)]
+    no_cache: bool,
+}
+
+impl From<Retrieve> for retrieve::Options {
+    fn from(retr: Retrieve) -> Self {
+        Self {
+            path: retr.path,
+            range: retr.range,
+            branch: retr.branch,
+            remote: retr.remote,
+            repo_url: retr.repo_url,
+            local: retr.local,
+            tracking: retr.tracking,
+            fetch: retr.fetch,
+            start: retr.start,
+            end: retr.end,
+            exclude: retr.exclude,
+            exclude_bots: retr.exclude_bots,
+            paths: retr.paths,
+            no_merges: retr.no_merges,
+            first_parent: retr.first_parent,
+            prs: retr.prs,
+            github_logins: retr.github_logins,
+            new_contributors: retr.new_contributors,
+            from_tag: retr.from_tag,
+            since_last_tag: retr.since_last_tag,
+            to_tag: retr.to_tag,
+            github: retr.github,
+            web_url: retr.web_url,
+            api_url: retr.api_url,
+            gitlab: retr.gitlab,
+            gitlab_url: retr.gitlab_url,
+            from: retr.from,
+            to: retr.to,
+            reviewers: retr.reviewers,
+            labels: retr.labels,
+            release_notes: retr.release_notes,
+            jobs: retr.jobs,
+            config: retr.config,
+            notes_ref: retr.notes_ref,
+            since: retr.since,
+            until: retr.until,
+            no_cache: retr.no_cache,
+        }
+    }
 }
 
 /// Generate markdown-formatted output from json input.
 #[derive(Parser)]
 #[clap(version = "0.2.0")]
 struct Generate {
-    /// Path to input file.
+    /// Paths to input files.
+    ///
+    /// The releases of all files are merged into one. A path of `-` denotes
+    /// standard input. If no paths are present, standard input will be used instead.
     ///
-    /// If the path is absent, standard input will be used instead.
+    /// If the only path is a directory of stored releases (see the `save` subcommand),
+    /// every release in it is generated, newest first.
     #[clap(parse(from_os_str))]
-    path: Option<PathBuf>,
+    paths: Vec<PathBuf>,
+    /// The format of the input: `json`, `toml`, or `yaml`.
+    ///
+    /// Defaults to the format detected by the extensions of the input files, or `json`.
+    #[clap(long, arg_enum, value_name = "FORMAT")]
+    input_format: Option<InputFormat>,
     /// Print example input.
     #[clap(long)]
     example: bool,
@@ -67,38 +371,532 @@ struct Generate {
     /// Print gotchas of this command's output.
     #[clap(long)]
     gotchas: bool,
+    /// Path to the file where the output is written.
+    ///
+    /// If the path is absent, the output will be printed to standard output instead.
+    #[clap(short, long, parse(from_os_str), env = "RELEASE_MAKER_OUTPUT")]
+    output: Option<PathBuf>,
+    /// Path to a directory where the output of every format is written,
+    /// as `release.<extension>`, such as `release.md` and `release.html`.
+    ///
+    /// Required if multiple formats are given.
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_name = "DIR",
+        conflicts_with = "output"
+    )]
+    out_dir: Option<PathBuf>,
+    /// Path to a Handlebars template of the output, taking precedence over `--format`.
+    ///
+    /// The template is rendered with the release as it is output by `--format json-resolved`.
+    /// `{{heading section}}` renders the heading of a section, such as `Added`.
+    #[clap(
+        short,
+        long,
+        parse(from_os_str),
+        value_name = "PATH",
+        env = "RELEASE_MAKER_TEMPLATE",
+        conflicts_with = "out-dir"
+    )]
+    template: Option<PathBuf>,
+    /// Re-render the output every time the input file changes.
+    ///
+    /// Requires both input paths and an output path or directory to be defined.
+    /// Standard input cannot be watched.
+    #[clap(short, long, requires = "paths")]
+    watch: bool,
+    /// Post the output to a Slack incoming webhook, instead of writing it.
+    ///
+    /// Requires `--format slack`.
+    #[clap(
+        long,
+        value_name = "URL",
+        conflicts_with_all = &["output", "out-dir", "watch"]
+    )]
+    post_webhook: Option<String>,
+    /// Print the Markdown output rendered for the terminal, with bold and colored text
+    /// instead of markup, to review it before publishing.
+    ///
+    /// The output of the profile, if any, is not written.
+    #[clap(
+        long,
+        conflicts_with_all = &["output", "out-dir", "watch", "post-webhook"]
+    )]
+    preview: bool,
+    /// Define a variable as `key=value`, which may be used as a `{{ key }}` placeholder
+    /// in the intro and epilogue. Environment variables are available as `{{ env.NAME }}`.
+    #[clap(long = "var", value_name = "KEY=VALUE", parse(try_from_str = parse_var))]
+    vars: Vec<(String, String)>,
+    /// The format of the output. Defaults to `markdown`.
+    ///
+    /// Multiple formats may be given, separated by commas, such as `markdown,html`,
+    /// which requires `--out-dir`. The input is read and processed once for all of them.
+    #[clap(
+        short,
+        long,
+        arg_enum,
+        env = "RELEASE_MAKER_FORMAT",
+        use_value_delimiter = true
+    )]
+    format: Vec<Format>,
+    /// Write a summary of the release near the top, such as
+    /// "42 changes from 13 contributors across 97 commits".
+    ///
+    /// A summary defined in the input takes precedence.
+    #[clap(long)]
+    summary: bool,
+    /// A template of the summary, implying `--summary`.
+    ///
+    /// The `{{ changes }}`, `{{ contributors }}` and `{{ commits }}` placeholders
+    /// are replaced with the respective counts.
+    #[clap(long, value_name = "TEMPLATE")]
+    summary_template: Option<String>,
+    /// A template of the URL to the profile of an author, such as
+    /// `https://gitlab.example.com/{name}`, where `{name}` is replaced with the name of the author.
+    ///
+    /// Takes precedence over the template defined in the input. If neither is defined,
+    /// profiles are assumed to be at the root of the host of the repository.
+    #[clap(long, value_name = "TEMPLATE", env = "RELEASE_MAKER_AUTHOR_URL")]
+    author_url: Option<String>,
+    /// How to render authors that are not described by a username: as plain `text`,
+    /// as a `mailto` link, or as a `link` to a profile as if their name was a username.
+    ///
+    /// Takes precedence over the choice defined in the input. Defaults to `text`.
+    #[clap(
+        long,
+        value_name = "FALLBACK",
+        possible_values = &["text", "mailto", "link"],
+        env = "RELEASE_MAKER_AUTHOR_FALLBACK"
+    )]
+    author_fallback: Option<AuthorFallback>,
+    /// The host of the repositories, which determines the links to commits and pull requests:
+    /// `github`, `gitlab`, `gitea` (including Forgejo and Codeberg), or `azure` (Azure DevOps).
+    ///
+    /// Takes precedence over the forge defined in the input. If neither is defined,
+    /// it is detected from the URL to the repository.
+    #[clap(long, arg_enum, value_name = "FORGE", env = "RELEASE_MAKER_FORGE")]
+    forge: Option<Forge>,
+    /// Warn about titles of changes that start with a lowercase letter, end with a period,
+    /// are too long, or start with a redundant verb.
+    ///
+    /// The maximum length and the redundant verbs are defined in the `[style]` table
+    /// of the configuration.
+    #[clap(long)]
+    lint: bool,
+    /// Truncate titles of changes longer than a number of characters with an ellipsis,
+    /// preferably at a word boundary. The full titles are kept as the descriptions of the changes.
+    #[clap(long, value_name = "LENGTH")]
+    truncate_titles: Option<usize>,
+    /// The names of sections in the order they are rendered, separated by commas,
+    /// such as `fixed,added`. Sections that are not named follow in their usual order.
+    #[clap(long, value_name = "SECTIONS", use_value_delimiter = true)]
+    section_order: Vec<String>,
+    /// Define the heading of a section as `name=heading`, such as `added=Features`.
+    #[clap(long = "heading", value_name = "NAME=HEADING", parse(try_from_str = parse_var))]
+    headings: Vec<(String, String)>,
+    /// The number of characters that commit hashes are abbreviated to, at least 4.
+    ///
+    /// Takes precedence over the length defined in the input. Defaults to 7.
+    #[clap(long, value_name = "LENGTH")]
+    hash_length: Option<usize>,
+    /// Write categories and names of changes as they are in Markdown, rather than escaping
+    /// characters such as `*`, `_` and `[`.
+    ///
+    /// Useful if names deliberately contain Markdown.
+    #[clap(long)]
+    raw_names: bool,
+    /// The language of the headings of sections and the other texts of the output:
+    /// `en` or `de`. Defaults to `en`.
+    ///
+    /// Headings and texts defined in the configuration or the input take precedence.
+    #[clap(long, arg_enum, value_name = "LANG", env = "RELEASE_MAKER_LANG")]
+    lang: Option<Language>,
+    /// Path to directory of a Git repository, in which abbreviated commit hashes
+    /// of the input are looked up to expand them to full hashes.
+    #[clap(long, parse(from_os_str), value_name = "PATH")]
+    repository: Option<PathBuf>,
+    /// Path to the configuration file.
+    ///
+    /// Defaults to `.release-maker.toml` in the root of the repository of the current directory,
+    /// if it exists.
+    #[clap(short, long, parse(from_os_str), env = "RELEASE_MAKER_CONFIG")]
+    config: Option<PathBuf>,
+    /// The name of a profile of the configuration, such as `github-release` for
+    /// `[profile.github-release]`, whose options are used unless given on the command line.
+    #[clap(short, long, value_name = "NAME", env = "RELEASE_MAKER_PROFILE")]
+    profile: Option<String>,
 }
 
-fn generate_release(repo_url: String, commits: impl Iterator<Item = Commit>) -> Release {
-    Release {
-        repo_url,
-        added: commits
-            .map(|commit| Change::new("any", commit.message, commit.author.name, commit.hash))
-            .collect(),
-        ..Default::default()
-    }
+/// Store a release as `<version>.json` in a directory of releases.
+///
+/// Stored releases serve as the source of truth of a project's history, and can
+/// be generated all at once by passing the directory to the `generate` subcommand.
+#[derive(Parser)]
+#[clap(version = "0.2.0")]
+struct Save {
+    /// The version of the release, such as `v1.3.0`.
+    version: String,
+    /// Paths to input files.
+    ///
+    /// The releases of all files are merged into one. A path of `-` denotes
+    /// standard input. If no paths are present, standard input will be used instead.
+    #[clap(parse(from_os_str))]
+    paths: Vec<PathBuf>,
+    /// The directory of stored releases.
+    #[clap(short, long, parse(from_os_str), default_value = store::DEFAULT_DIR)]
+    dir: PathBuf,
+    /// Overwrite the release if it is already stored.
+    #[clap(short, long)]
+    force: bool,
 }
 
-fn retrieve(retr: Retrieve) -> Result<()> {
-    let repo = Repository::open(&retr.path)?;
-    let mut commits = repo.commits(&retr.branch)?;
+/// List the past releases of a repository, as defined by its tags.
+///
+/// Each release is listed with the date of its tag, the number of commits
+/// since the previous release, and whether the release is stored.
+#[derive(Parser)]
+#[clap(version = "0.2.0")]
+struct History {
+    /// Path to directory of a Git repository.
+    #[clap(parse(from_os_str), default_value = ".")]
+    path: PathBuf,
+    /// A glob pattern that the names of release tags match.
+    #[clap(short, long, default_value = "v*")]
+    pattern: String,
+    /// The directory of stored releases.
+    #[clap(short, long, parse(from_os_str), default_value = store::DEFAULT_DIR)]
+    dir: PathBuf,
+}
 
-    if let Some(start) = retr.start {
-        commits = commits.start(&start);
-    }
+/// Print the generated output of a specific release.
+///
+/// The release is loaded from the directory of stored releases. If it is not stored,
+/// it is retrieved from the range of commits between its tag and the previous release's tag.
+#[derive(Parser)]
+#[clap(version = "0.2.0")]
+struct Show {
+    /// The version of the release, such as `v1.2.0`.
+    version: String,
+    /// Path to directory of a Git repository.
+    #[clap(parse(from_os_str), default_value = ".")]
+    path: PathBuf,
+    /// A glob pattern that the names of release tags match.
+    #[clap(short, long, default_value = "v*")]
+    pattern: String,
+    /// The directory of stored releases.
+    #[clap(short, long, parse(from_os_str), default_value = store::DEFAULT_DIR)]
+    dir: PathBuf,
+    /// Define a variable as `key=value`, which may be used as a `{{ key }}` placeholder
+    /// in the intro and epilogue. Environment variables are available as `{{ env.NAME }}`.
+    #[clap(long = "var", value_name = "KEY=VALUE", parse(try_from_str = parse_var))]
+    vars: Vec<(String, String)>,
+    /// The format of the output.
+    #[clap(short, long, arg_enum, default_value = "markdown")]
+    format: Format,
+}
 
-    if let Some(end) = retr.end {
-        commits = commits.end(&end);
-    }
+/// Print the section of the most recent version in an existing changelog.
+///
+/// Useful for passing the notes of a release to other tools, such as `gh release create --notes-file -`.
+#[derive(Parser)]
+#[clap(version = "0.2.0")]
+struct Latest {
+    /// Path to the changelog.
+    ///
+    /// A path of `-` denotes standard input.
+    #[clap(parse(from_os_str), default_value = "CHANGELOG.md")]
+    path: PathBuf,
+    /// Include the heading of the section in the output.
+    #[clap(long)]
+    heading: bool,
+}
+
+/// Install Git hooks and a commit template that nudge contributors towards commit messages
+/// that `retrieve` categorizes automatically.
+///
+/// Installs a `commit-msg` hook that warns about messages that neither follow Conventional Commits
+/// nor have a `Changelog` trailer, a `prepare-commit-msg` hook that reminds of the trailer,
+/// and a commit template describing both formats.
+#[derive(Parser)]
+#[clap(version = "0.2.0")]
+struct InstallHooks {
+    /// Path to directory of a Git repository.
+    #[clap(parse(from_os_str), default_value = ".")]
+    path: PathBuf,
+    /// Overwrite existing hooks that were not installed by this tool.
+    #[clap(short, long)]
+    force: bool,
+}
+
+/// Publish a release as a GitHub Release, or as a release of a Gitea or Forgejo instance,
+/// printing the URL to it.
+///
+/// The body of the release is generated as markdown from the input, or read from a file
+/// with `--body`. Requires a token that may push to the repository, given by `--token`
+/// or else discovered as by `retrieve --github`, or for Gitea, by the `RELEASE_MAKER_GITEA_TOKEN`
+/// or `GITEA_TOKEN` environment variable.
+#[derive(Parser)]
+#[clap(version = "0.2.0")]
+struct Publish {
+    /// The tag of the release, such as `v0.5.0`. The tag is created if it does not exist.
+    tag: String,
+    /// Paths to input files.
+    ///
+    /// The releases of all files are merged into one. A path of `-` denotes
+    /// standard input. If no paths are present, standard input will be used instead.
+    #[clap(parse(from_os_str))]
+    paths: Vec<PathBuf>,
+    /// Path to a file with the body of the release, such as the output of `generate`,
+    /// instead of input files. A path of `-` denotes standard input.
+    #[clap(
+        long,
+        parse(from_os_str),
+        value_name = "PATH",
+        conflicts_with = "paths"
+    )]
+    body: Option<PathBuf>,
+    /// The repository, as `owner/name`.
+    ///
+    /// Defaults to the repository of the input, or of the `origin` remote of the
+    /// current directory if the body is read from a file.
+    #[clap(long, value_name = "OWNER/NAME")]
+    repo: Option<String>,
+    /// The forge to publish to: `github`, or `gitea` (including Forgejo and Codeberg).
+    /// Releases cannot be published to the other forges.
+    ///
+    /// Defaults to the forge of the input, or else is detected from the URL to the repository.
+    #[clap(long, arg_enum, value_name = "FORGE")]
+    forge: Option<Forge>,
+    /// The title of the release. Defaults to the tag.
+    #[clap(long)]
+    title: Option<String>,
+    /// Create the release as a draft, which is only visible to collaborators.
+    #[clap(long)]
+    draft: bool,
+    /// Mark the release as a pre-release.
+    #[clap(long)]
+    prerelease: bool,
+    /// The token to authenticate to the forge with.
+    ///
+    /// For GitHub, defaults to the `RELEASE_MAKER_GITHUB_TOKEN`, `GITHUB_TOKEN`, or `GH_TOKEN`
+    /// environment variable, the `gh` CLI's login, or the `token` of the `[github]` table
+    /// of the configuration, in that order. For Gitea, defaults to the `RELEASE_MAKER_GITEA_TOKEN`
+    /// or `GITEA_TOKEN` environment variable. As these differ by forge, the token itself is not
+    /// read from an environment variable.
+    #[clap(long)]
+    token: Option<String>,
+    /// The URL of a GitHub Enterprise Server or of a Gitea instance, such as
+    /// `https://github.example.com`, to publish to.
+    ///
+    /// Defaults to GitHub itself, or for Gitea, to the host of the URL to the repository.
+    #[clap(long, value_name = "URL")]
+    web_url: Option<String>,
+    /// The URL of the REST API of a GitHub Enterprise Server.
+    ///
+    /// Defaults to `/api/v3` of `--web-url`.
+    #[clap(long, value_name = "URL")]
+    api_url: Option<String>,
+}
+
+/// Add the changes of a release to a changelog in the format of Keep a Changelog.
+///
+/// The changes are inserted above the previous versions under an `## [Unreleased]` heading,
+/// or under the heading of a version with `--version`, replacing an existing unreleased section.
+/// The rest of the changelog is preserved, and the link references at its bottom are updated.
+#[derive(Parser)]
+#[clap(version = "0.2.0")]
+struct Changelog {
+    /// Paths to input files, or to a directory of stored releases, such as `.releases`.
+    ///
+    /// The releases of all files are merged into one. A path of `-` denotes
+    /// standard input. If no paths are present, standard input will be used instead.
+    /// Every release of a directory is added as the version of its file name.
+    #[clap(parse(from_os_str))]
+    paths: Vec<PathBuf>,
+    /// Path to the changelog. It is created if it does not exist.
+    #[clap(short, long, parse(from_os_str), default_value = "CHANGELOG.md")]
+    file: PathBuf,
+    /// The version of the release, such as `v1.2.0`. Without it, the changes are unreleased.
+    #[clap(long)]
+    version: Option<String>,
+    /// The date of the release. Defaults to today.
+    #[clap(long, requires = "version")]
+    date: Option<String>,
+}
+
+/// Suggest the next version by semantic versioning, from the changes since the latest release tag.
+///
+/// Breaking changes, marked by `!` after the type of a Conventional Commit or by a `BREAKING CHANGE`
+/// footer, bump the major version, features (`feat`) bump the minor version, and other commits bump
+/// the patch version. Before 1.0.0, breaking changes bump the minor version and other commits
+/// bump the patch version.
+#[derive(Parser)]
+#[clap(version = "0.2.0")]
+struct NextVersion {
+    /// Path to directory of a Git repository.
+    #[clap(parse(from_os_str), default_value = ".")]
+    path: PathBuf,
+    /// The branch whose commits since the latest tag are analyzed.
+    ///
+    /// Defaults to the branch of the `[retrieve]` table of the configuration,
+    /// or the default branch of the remote if left undefined.
+    #[clap(short, long, env = "RELEASE_MAKER_BRANCH")]
+    branch: Option<String>,
+    /// The remote of the branch.
+    ///
+    /// Defaults to the remote of the `[retrieve]` table of the configuration,
+    /// or `origin` if left undefined.
+    #[clap(long, env = "RELEASE_MAKER_REMOTE")]
+    remote: Option<String>,
+    /// A glob pattern that the names of release tags match.
+    #[clap(long, default_value = "v*")]
+    pattern: String,
+    /// Analyze the sections of a release file instead of the commits, where breaking and removed
+    /// changes are breaking and added changes are features. A path of `-` denotes standard input.
+    #[clap(long, parse(from_os_str), value_name = "PATH")]
+    release: Option<PathBuf>,
+    /// Write the version into the `Cargo.toml` of the repository.
+    #[clap(long)]
+    apply: bool,
+    /// Path to the manifest that `--apply` writes the version into.
+    ///
+    /// Defaults to `Cargo.toml` in the root of the repository.
+    #[clap(long, parse(from_os_str), value_name = "PATH", requires = "apply")]
+    manifest_path: Option<PathBuf>,
+    /// Path to the configuration file.
+    ///
+    /// Defaults to `.release-maker.toml` in the root of the repository, if it exists.
+    #[clap(short, long, parse(from_os_str), env = "RELEASE_MAKER_CONFIG")]
+    config: Option<PathBuf>,
+}
+
+/// Retrieve the commits of a repository and generate the output of their release
+/// in one invocation, without an intermediate file.
+///
+/// Takes the options of `retrieve`, where `--output` is the path to the generated output.
+/// The output is generated with the options of the `[generate]` table of the configuration,
+/// or of the profile given by `--profile`.
+#[derive(Parser)]
+#[clap(version = "0.2.0")]
+struct Run {
+    #[clap(flatten)]
+    retrieve: Retrieve,
+    /// The format of the output. Defaults to the format of the profile, or `markdown`.
+    #[clap(short, long, arg_enum, env = "RELEASE_MAKER_FORMAT")]
+    format: Option<Format>,
+    /// The name of a profile of the configuration, such as `github-release` for
+    /// `[profile.github-release]`, whose options are used to generate the output.
+    #[clap(short, long, value_name = "NAME", env = "RELEASE_MAKER_PROFILE")]
+    profile: Option<String>,
+    /// Define a variable as `key=value`, which may be used as a `{{ key }}` placeholder
+    /// in the intro and epilogue. Environment variables are available as `{{ env.NAME }}`.
+    #[clap(long = "var", value_name = "KEY=VALUE", parse(try_from_str = parse_var))]
+    vars: Vec<(String, String)>,
+    /// Path to a file where the retrieved release is also written as json,
+    /// as it would be output by `retrieve`.
+    #[clap(long, parse(from_os_str), value_name = "PATH")]
+    save_json: Option<PathBuf>,
+}
+
+/// Check input files for problems without generating output: empty categories,
+/// short commit hashes, unknown keys, duplicate changes, authors that are email addresses,
+/// and a missing `repo_url`.
+///
+/// Problems are printed with the line and column of the offending value.
+#[derive(Parser)]
+#[clap(version = "0.2.0")]
+struct Lint {
+    /// Paths to JSON input files. A path of `-` denotes standard input.
+    /// If no paths are present, standard input will be used instead.
+    #[clap(parse(from_os_str))]
+    paths: Vec<PathBuf>,
+    /// Path to the configuration file.
+    ///
+    /// Defaults to `.release-maker.toml` in the root of the repository, if it exists.
+    #[clap(short, long, parse(from_os_str), env = "RELEASE_MAKER_CONFIG")]
+    config: Option<PathBuf>,
+    /// The name of a profile of the configuration, whose hash length is checked.
+    #[clap(short, long, value_name = "NAME", env = "RELEASE_MAKER_PROFILE")]
+    profile: Option<String>,
+    /// The number of characters that commit hashes are abbreviated to, which they should not
+    /// be shorter than.
+    ///
+    /// Takes precedence over the length defined in the input. Defaults to 7.
+    #[clap(long, value_name = "LENGTH")]
+    hash_length: Option<usize>,
+}
 
-    let release = generate_release(repo.url()?, commits);
+/// Print a script that completes the subcommands and options of release-maker in a shell.
+///
+/// For example, install the completions for bash with
+/// `release-maker completions bash > ~/.local/share/bash-completion/completions/release-maker`.
+#[derive(Parser)]
+#[clap(version = "0.2.0")]
+struct Completions {
+    /// The shell of the script: `bash`, `zsh`, `fish`, `powershell`, or `elvish`.
+    #[clap(arg_enum)]
+    shell: Shell,
+}
 
-    println!("{}", to_string_pretty(&release)?);
+/// The format of the report of the `report` subcommand.
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ReportFormat {
+    /// A table for reading, followed by averages.
+    Table,
+    /// JSON, for processing.
+    Json,
+    /// Comma-separated values, for spreadsheets.
+    Csv,
+}
+
+/// Report on the cadence of releases, as defined by the repository's tags:
+/// the days between releases, the changes per release, and the growth of contributors.
+///
+/// Stored releases are used to count changes and contributors. For releases that are not stored,
+/// the commits since the previous release are used instead.
+#[derive(Parser)]
+#[clap(version = "0.2.0")]
+struct Report {
+    /// Path to directory of a Git repository.
+    #[clap(parse(from_os_str), default_value = ".")]
+    path: PathBuf,
+    /// A glob pattern that the names of release tags match.
+    #[clap(short, long, default_value = "v*")]
+    pattern: String,
+    /// The directory of stored releases.
+    #[clap(short, long, parse(from_os_str), default_value = store::DEFAULT_DIR)]
+    dir: PathBuf,
+    /// The format of the report.
+    #[clap(short, long, arg_enum, default_value = "table")]
+    format: ReportFormat,
+}
+
+fn retrieve(mut retr: Retrieve, dry_run: bool) -> Result<()> {
+    let output = retr.output.take();
+    let force = retr.force;
+
+    let release = retrieve::retrieve_release(retr.into(), dry_run)?;
+
+    match output {
+        Some(path) => {
+            if !force {
+                check_overwrite(&path)?;
+            }
+
+            if dry_run {
+                print_dry_write(&path, &to_string_pretty(&release)?);
+            } else {
+                write_file(&path, |file| write_json(file, &release))?;
+            }
+        }
+        None => write_json(io::stdout().lock(), &release)?,
+    }
 
     Ok(())
 }
 
-fn generate(gen: Generate) -> Result<()> {
+fn generate(gen: Generate, dry_run: bool) -> Result<()> {
     if gen.example {
         print!("{}", EXAMPLE);
     }
@@ -123,26 +921,899 @@ fn generate(gen: Generate) -> Result<()> {
         return Ok(());
     }
 
-    let reader: Box<dyn std::io::Read> = match gen.path {
-        Some(path) => Box::new(File::open(path)?),
-        None => Box::new(std::io::stdin()),
+    if gen.watch && dry_run {
+        return Err("`--watch` cannot be used with `--dry-run`".into());
+    }
+
+    let config = Config::discover(&repo_root(Path::new(".")), gen.config.as_deref())?;
+
+    // Options on the command line take precedence over those of the profile, and variables
+    // and headings on the command line are defined last.
+    let profile = Profile {
+        output: gen.output,
+        out_dir: gen.out_dir,
+        template: gen.template,
+        summary: gen.summary,
+        summary_template: gen.summary_template,
+        author_url: gen.author_url,
+        author_fallback: gen.author_fallback,
+        forge: gen.forge,
+        lint: gen.lint,
+        truncate_titles: gen.truncate_titles,
+        vars: gen.vars.into_iter().collect(),
+        section_order: gen.section_order,
+        headings: gen.headings.into_iter().collect(),
+        hash_length: gen.hash_length,
+        raw_names: gen.raw_names,
+        lang: gen.lang,
+        ..Profile::default()
+    }
+    .or(config.profile(gen.profile.as_deref())?);
+
+    let mut formats = gen.format;
+
+    if formats.is_empty() {
+        formats.push(profile.format.unwrap_or(Format::Markdown));
+    }
+
+    let renderer = Renderer {
+        repository: gen.repository.map(Repository::discover).transpose()?,
+        input_format: gen.input_format,
+        ..Renderer::from_profile(&profile, &config)?
+    };
+
+    if let Some(url) = &gen.post_webhook {
+        if formats != [Format::Slack] {
+            return Err("`--post-webhook` requires `--format slack`".into());
+        }
+
+        let input = renderer.load(&gen.paths)?;
+        let text = renderer.render(Format::Slack, &input)?;
+
+        if dry_run {
+            println!("Would post to the webhook:\n");
+            print_excerpt(&text);
+        } else {
+            slack::post_webhook(url, &text)?;
+        }
+
+        return Ok(());
+    }
+
+    // The preview is printed even if the profile defines an output.
+    if gen.preview {
+        if !matches!(
+            formats[..],
+            [Format::Markdown | Format::KeepAChangelog | Format::Discord]
+        ) {
+            return Err("`--preview` requires a single Markdown format".into());
+        }
+
+        let input = renderer.load(&gen.paths)?;
+        print!(
+            "{}",
+            preview::render(&renderer.render(formats[0], &input)?, preview::use_color())
+        );
+
+        return Ok(());
+    }
+
+    let targets = match (profile.output, profile.out_dir) {
+        (_, Some(dir)) => {
+            if !dry_run {
+                std::fs::create_dir_all(&dir)
+                    .map_err(|err| format!("{}: {}", dir.display(), err))?;
+            }
+
+            formats
+                .iter()
+                .map(|&format| (format, dir.join(format!("release.{}", format.extension()))))
+                .collect()
+        }
+        _ if formats.len() > 1 => return Err("multiple formats require `--out-dir`".into()),
+        (Some(output), None) => vec![(formats[0], output)],
+        (None, None) if gen.watch => {
+            return Err("`--watch` requires an output path or directory".into())
+        }
+        (None, None) => {
+            let input = renderer.load(&gen.paths)?;
+            renderer.print(formats[0], &input)?;
+
+            return Ok(());
+        }
+    };
+
+    if gen.watch {
+        return watch(&gen.paths, &targets, &renderer);
+    }
+
+    if dry_run {
+        let input = renderer.load(&gen.paths)?;
+
+        for (format, path) in &targets {
+            print_dry_write(path, &renderer.render(*format, &input)?);
+        }
+
+        return Ok(());
+    }
+
+    renderer.write_targets(&gen.paths, &targets)
+}
+
+/// Returns an error if the output file exists, so that it is only overwritten with `--force`.
+fn check_overwrite(path: &Path) -> Result<()> {
+    if path.exists() {
+        return Err(format!(
+            "{} already exists; use `--force` to overwrite it",
+            path.display()
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Print the path and the first lines of a file that a dry run would write.
+fn print_dry_write(path: &Path, text: &str) {
+    println!("Would write {}:\n", path.display());
+    print_excerpt(text);
+}
+
+/// Print the first lines of a text, indented, followed by the number of omitted lines.
+fn print_excerpt(text: &str) {
+    let lines = text.lines().count();
+
+    for line in text.lines().take(DRY_RUN_LINES) {
+        println!("    {}", line);
+    }
+
+    if lines > DRY_RUN_LINES {
+        println!("    ({} more lines)", lines - DRY_RUN_LINES);
+    }
+}
+
+fn save(sav: Save, dry_run: bool) -> Result<()> {
+    let store = Store::new(sav.dir);
+    let path = store.path(&sav.version)?;
+
+    if path.is_file() && !sav.force {
+        return Err(format!(
+            "{} already exists; use `--force` to overwrite it",
+            path.display()
+        )
+        .into());
+    }
+
+    let release = read_releases(&sav.paths, None)?;
+
+    if dry_run {
+        print_dry_write(&path, &serde_json::to_string_pretty(&release)?);
+
+        return Ok(());
+    }
+
+    let path = store.save(&sav.version, &release)?;
+
+    eprintln!("Saved {}", path.display());
+
+    Ok(())
+}
+
+fn history(hist: History) -> Result<()> {
+    let repo = Repository::discover(&hist.path)?;
+    let store = Store::new(repo.root().join(&hist.dir));
+
+    let mut tags = repo.tags(&hist.pattern)?;
+    tags.sort_by(|a, b| store::cmp_versions(&a.name, &b.name));
+
+    let width = tags.iter().map(|tag| tag.name.len()).max().unwrap_or(0);
+
+    for (i, tag) in tags.iter().enumerate() {
+        let previous = tags.get(i + 1).map(|tag| tag.hash.as_str());
+        let count = repo.count_commits(previous, &tag.hash)?;
+
+        let line = format!(
+            "{:width$}  {}  {:>5} {:7}  {}",
+            tag.name,
+            tag.time.format("%Y-%m-%d"),
+            count,
+            if count == 1 { "commit" } else { "commits" },
+            if store.contains(&tag.name) {
+                "stored"
+            } else {
+                ""
+            },
+            width = width,
+        );
+
+        println!("{}", line.trim_end());
+    }
+
+    Ok(())
+}
+
+fn show(show: Show) -> Result<()> {
+    let store = Store::new(repo_root(&show.path).join(&show.dir));
+
+    // Stored releases may abbreviate their hashes, which are expanded if there is a repository.
+    let (release, repo) = if store.contains(&show.version) {
+        (
+            store.load(&show.version)?,
+            Repository::discover(&show.path).ok(),
+        )
+    } else {
+        let repo = Repository::discover(&show.path)?;
+
+        let mut tags = repo.tags(&show.pattern)?;
+        tags.sort_by(|a, b| store::cmp_versions(&a.name, &b.name));
+
+        let index = match tags.iter().position(|tag| tag.name == show.version) {
+            Some(index) => index,
+            None => {
+                return Err(format!(
+                    "no release of `{}` is stored, nor is there a tag of that name matching `{}`",
+                    show.version, show.pattern
+                )
+                .into())
+            }
+        };
+
+        let previous = tags.get(index + 1).map(|tag| tag.hash.as_str());
+        let commits = repo
+            .range(previous, &tags[index].hash)?
+            .notes(categorize::NOTES_REF);
+
+        let commits = commits.collect::<Result<Vec<_>, _>>()?;
+
+        (
+            generate_release(repo.url()?, commits.into_iter(), &mut Cache::disabled())?,
+            Some(repo),
+        )
     };
 
-    let mut reader = std::io::BufReader::new(reader);
-    let release = serde_json::from_reader(&mut reader)?;
+    let renderer = Renderer {
+        placeholders: Placeholders::new(show.vars),
+        repository: repo,
+        ..Renderer::default()
+    };
 
     let mut res = String::new();
-    generate_msg(&mut res, &release)?;
+
+    if let Some(sep) = show.format.separator() {
+        csv::write_header(&mut res, sep, false)?;
+    }
+
+    let release = renderer.prepare(release)?;
+    renderer.write_release(&mut res, show.format, None, &release)?;
     println!("{}", res);
 
     Ok(())
 }
 
-fn main() -> Result<()> {
-    let app = App::parse();
+fn latest(latest: Latest) -> Result<()> {
+    let text = if is_stdin(&latest.path) {
+        let mut text = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut text)?;
+        text
+    } else {
+        std::fs::read_to_string(&latest.path)
+            .map_err(|err| format!("{}: {}", latest.path.display(), err))?
+    };
+
+    let section = match changelog::latest_section(&text) {
+        Some(section) => section,
+        None => return Err("the changelog does not contain any version headings".into()),
+    };
+
+    if latest.heading {
+        println!("{}\n", section.heading);
+    }
+
+    println!("{}", section.body);
+
+    Ok(())
+}
+
+fn update_changelog(cl: Changelog, dry_run: bool) -> Result<()> {
+    let renderer = Renderer::default();
+    let input = renderer.load(&cl.paths)?;
+
+    let mut text = match std::fs::read_to_string(&cl.file) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            "# Changelog\n\nAll notable changes to this project will be documented in this file.\n"
+                .to_string()
+        }
+        Err(err) => return Err(format!("{}: {}", cl.file.display(), err).into()),
+    };
+
+    let release = match input {
+        Input::Release(release) => release,
+        Input::Store(releases) => {
+            if cl.version.is_some() {
+                return Err(
+                    "the versions of stored releases are their file names; add a single stored \
+                     release by its file to give it another version"
+                        .into(),
+                );
+            }
+
+            // The versions are inserted oldest first, so that the newest ends up on top.
+            for (i, (version, release)) in releases.iter().enumerate().rev() {
+                let previous = releases
+                    .get(i + 1)
+                    .map(|(version, _)| version.as_str())
+                    .or(release.previous_version.as_deref());
+
+                let mut section = String::new();
+                keep_a_changelog::write_version(
+                    &mut section,
+                    &keep_a_changelog::Version {
+                        name: Some(version),
+                        date: release.date.clone(),
+                        release,
+                    },
+                )?;
+
+                let mut links = Vec::new();
+
+                if i == 0 {
+                    links.push((
+                        "Unreleased".to_string(),
+                        release.compare_url(version, "HEAD"),
+                    ));
+                }
+
+                links.extend(keep_a_changelog::link(release, Some(version), previous));
+                text = changelog::insert_section(&text, Some(version), &section, &links);
+            }
+
+            return write_changelog(&cl.file, &text, dry_run);
+        }
+    };
+
+    let version = cl.version.as_deref();
+
+    let previous = changelog::sections(&text)
+        .into_iter()
+        .map(|section| section.version().to_string())
+        .find(|name| Some(name.as_str()) != version);
+
+    let date = version.map(|_| match &cl.date {
+        Some(date) => date.clone(),
+        None => chrono::Local::now().format("%Y-%m-%d").to_string(),
+    });
+
+    let mut section = String::new();
+    keep_a_changelog::write_version(
+        &mut section,
+        &keep_a_changelog::Version {
+            name: version,
+            date,
+            release: &release,
+        },
+    )?;
+
+    let mut links = Vec::new();
+
+    if let Some(version) = version {
+        links.push((
+            "Unreleased".to_string(),
+            release.compare_url(version, "HEAD"),
+        ));
+    }
+
+    links.extend(keep_a_changelog::link(
+        &release,
+        version,
+        previous.as_deref(),
+    ));
+
+    let text = changelog::insert_section(&text, version, &section, &links);
+
+    write_changelog(&cl.file, &text, dry_run)
+}
+
+/// Write the updated changelog, or print it in a dry run.
+fn write_changelog(path: &Path, text: &str, dry_run: bool) -> Result<()> {
+    if dry_run {
+        print_dry_write(path, text);
+    } else {
+        std::fs::write(path, text).map_err(|err| format!("{}: {}", path.display(), err))?;
+    }
+
+    Ok(())
+}
+
+/// A client of the API of a forge that releases are published to.
+enum Publisher {
+    Github(github::Client),
+    Gitea(gitea::Client),
+}
+
+impl Publisher {
+    fn check_release(&self, repo: &str, tag: &str) -> Result<()> {
+        match self {
+            Publisher::Github(client) => client.check_release(repo, tag),
+            Publisher::Gitea(client) => client.check_release(repo, tag),
+        }
+    }
+
+    fn create_release(&self, repo: &str, release: &github::NewRelease) -> Result<String> {
+        match self {
+            Publisher::Github(client) => client.create_release(repo, release),
+            Publisher::Gitea(client) => client.create_release(repo, release),
+        }
+    }
+}
+
+/// The token to authenticate to the API of a Gitea instance with, if any.
+fn gitea_token() -> Option<String> {
+    std::env::var("RELEASE_MAKER_GITEA_TOKEN")
+        .or_else(|_| std::env::var("GITEA_TOKEN"))
+        .ok()
+}
+
+fn publish(publ: Publish, dry_run: bool) -> Result<()> {
+    let config = Config::discover(&repo_root(Path::new(".")), None)?;
+
+    let (repo_url, forge, body) = match &publ.body {
+        Some(path) => {
+            let body = if is_stdin(path) {
+                let mut text = String::new();
+                std::io::Read::read_to_string(&mut std::io::stdin(), &mut text)?;
+                text
+            } else {
+                std::fs::read_to_string(path)
+                    .map_err(|err| format!("{}: {}", path.display(), err))?
+            };
+
+            (None, None, body)
+        }
+        None => {
+            let renderer = Renderer {
+                gitea: config.gitea.clone(),
+                ..Renderer::default()
+            };
+
+            let input = renderer.load(&publ.paths)?;
+
+            let release = match &input {
+                Input::Release(release) => release,
+                Input::Store(_) => {
+                    return Err(
+                        "publish a stored release by its file, such as `.releases/v1.0.0.json`"
+                            .into(),
+                    )
+                }
+            };
+
+            (
+                Some(release.repo_url.clone()),
+                release.forge,
+                renderer.render(Format::Markdown, &input)?,
+            )
+        }
+    };
+
+    // The URL to the repository is needed to find the repository if `--repo` is absent.
+    let repo_url = match repo_url {
+        Some(url) => Some(url),
+        None if publ.repo.is_none() => Some(Repository::discover(".")?.url()?),
+        None => None,
+    };
+    let repo_url = repo_url.map(|url| normalize_url(&url)).transpose()?;
+
+    let forge = publ
+        .forge
+        .or(forge)
+        .unwrap_or_else(|| match repo_url.as_deref() {
+            Some(url) if config.gitea.hosts(url) => Forge::Gitea,
+            Some(url) => Forge::detect(url),
+            None => Forge::Github,
+        });
+
+    let (repo, publisher) = match forge {
+        Forge::Github => {
+            let instance = config
+                .github
+                .instance(publ.web_url.as_deref(), publ.api_url.as_deref());
+
+            let repo = match (publ.repo, &repo_url) {
+                (Some(repo), _) => repo,
+                (None, Some(url)) => instance
+                    .repo_from_url(url)
+                    .ok_or_else(|| format!("{} is not a repository on {}", url, instance.web_url))?
+                    .to_string(),
+                (None, None) => unreachable!("the URL is present without `--repo`"),
+            };
+
+            let client = config.github.client(instance, publ.token);
+            (repo, Publisher::Github(client))
+        }
+        Forge::Gitea => {
+            let split = repo_url.as_deref().and_then(gitea::split_repo_url);
+
+            let url = match (publ.web_url, split) {
+                (Some(url), _) => url,
+                (None, Some((url, _))) => url.to_string(),
+                (None, None) => {
+                    return Err("give the URL of the Gitea instance with `--web-url`".into())
+                }
+            };
+
+            let repo = match (publ.repo, &repo_url) {
+                (Some(repo), _) => repo,
+                (None, Some(repo_url)) => split
+                    .map(|(_, repo)| repo.to_string())
+                    .ok_or_else(|| format!("{} is not a URL to a repository", repo_url))?,
+                (None, None) => unreachable!("the URL is present without `--repo`"),
+            };
+
+            let client = gitea::Client::new(&url, publ.token.or_else(gitea_token));
+            (repo, Publisher::Gitea(client))
+        }
+        Forge::Gitlab | Forge::Azure => {
+            return Err(format!(
+                "publishing to `{}` is not supported; publish to `github` or `gitea`",
+                forge
+                    .to_possible_value()
+                    .map_or("", |value| value.get_name())
+            )
+            .into())
+        }
+    };
+
+    let tag = publ.tag;
+    let release = github::NewRelease {
+        name: publ.title.unwrap_or_else(|| tag.clone()),
+        tag_name: tag,
+        body,
+        draft: publ.draft,
+        prerelease: publ.prerelease,
+    };
+
+    publisher.check_release(&repo, &release.tag_name)?;
+
+    if dry_run {
+        print_dry_run(&repo, &release);
+        return Ok(());
+    }
+
+    println!("{}", publisher.create_release(&repo, &release)?);
+
+    Ok(())
+}
+
+fn print_dry_run(repo: &str, release: &github::NewRelease) {
+    println!("Would create a release in {}:", repo);
+    println!("tag: {}", release.tag_name);
+    println!("title: {}", release.name);
+    println!("draft: {}", release.draft);
+    println!("prerelease: {}", release.prerelease);
+    println!("body:\n");
+    print_excerpt(&release.body);
+}
+
+fn next_version(ver: NextVersion, dry_run: bool) -> Result<()> {
+    let config = Config::discover(&repo_root(&ver.path), ver.config.as_deref())?;
+
+    let branch = ver.branch.or_else(|| config.retrieve.branch.clone());
+    let remote = ver
+        .remote
+        .or_else(|| config.retrieve.remote.clone())
+        .unwrap_or_else(|| "origin".to_string());
+
+    let repo = Repository::discover(&ver.path)?.with_remote(&remote);
+
+    let latest = repo
+        .tags(&ver.pattern)?
+        .into_iter()
+        .filter_map(|tag| store::parse_version(&tag.name).map(|version| (version, tag)))
+        .max_by(|(a, _), (b, _)| a.cmp(b));
+
+    let bump = match &ver.release {
+        Some(path) => {
+            let release = if is_stdin(path) {
+                serde_json::from_reader(std::io::stdin())?
+            } else {
+                let file =
+                    File::open(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+                serde_json::from_reader(file)
+                    .map_err(|err| format!("{}: {}", path.display(), err))?
+            };
+
+            bump::Bump::of_release(&release)
+        }
+        None => {
+            let branch = match branch {
+                Some(branch) => branch,
+                None => repo.default_branch()?,
+            };
+            let mut commits = repo.commits(&branch)?;
+
+            if let Some((_, tag)) = &latest {
+                commits = commits.exclude(&tag.hash)?;
+            }
+
+            let mut bump = None;
+
+            for commit in commits {
+                let commit = commit?;
+                bump = bump.max(Some(bump::Bump::of_commit(&commit.message, &commit.body)));
+            }
+
+            bump
+        }
+    };
+
+    let bump = match (bump, &latest) {
+        (Some(bump), _) => bump,
+        (None, Some((_, tag))) => {
+            return Err(format!("there are no changes since {}", tag.name).into())
+        }
+        (None, None) => return Err("there are no changes".into()),
+    };
+
+    let (next, prefix) = match &latest {
+        Some((version, tag)) => (
+            bump.apply(version),
+            if tag.name.starts_with('v') { "v" } else { "" },
+        ),
+        // The first release.
+        None => (semver::Version::new(0, 1, 0), "v"),
+    };
+
+    if ver.apply {
+        let manifest = ver
+            .manifest_path
+            .unwrap_or_else(|| repo.root().join("Cargo.toml"));
+
+        if dry_run {
+            print_dry_write(&manifest, &bump::manifest_with_version(&manifest, &next)?);
+        } else {
+            bump::set_manifest_version(&manifest, &next)?;
+        }
+    }
+
+    println!("{}{}", prefix, next);
+
+    Ok(())
+}
+
+fn report(rep: Report) -> Result<()> {
+    let repo = Repository::discover(&rep.path)?;
+    let store = Store::new(repo.root().join(&rep.dir));
+
+    let report = report::Report::new(&repo, &store, &rep.pattern)?;
+
+    let mut res = String::new();
+
+    match rep.format {
+        ReportFormat::Table => report.write_table(&mut res)?,
+        ReportFormat::Json => res = to_string_pretty(&report)? + "\n",
+        ReportFormat::Csv => report.write_csv(&mut res)?,
+    }
+
+    print!("{}", res);
+
+    Ok(())
+}
+
+fn run_all(run: Run, dry_run: bool) -> Result<()> {
+    let Run {
+        retrieve: mut retr,
+        format,
+        profile,
+        vars,
+        save_json,
+    } = run;
+
+    let config = Config::discover(&repo_root(&retr.path), retr.config.as_deref())?;
+    let mut profile = config.profile(profile.as_deref())?;
+    profile.vars.extend(vars);
+
+    let output = retr.output.take().or(profile.output.take());
+    let force = retr.force;
+    let repository = Repository::discover(&retr.path).ok();
+
+    if !force {
+        for path in output.iter().chain(&save_json) {
+            check_overwrite(path)?;
+        }
+    }
+
+    let release = retrieve::retrieve_release(retr.into(), dry_run)?;
+
+    if let Some(path) = save_json {
+        if dry_run {
+            print_dry_write(&path, &to_string_pretty(&release)?);
+        } else {
+            write_file(&path, |file| write_json(file, &release))?;
+        }
+    }
+
+    let renderer = Renderer {
+        repository,
+        ..Renderer::from_profile(&profile, &config)?
+    };
+
+    renderer.lint(None, &release);
+    let input = Input::Release(Box::new(renderer.prepare(release)?));
+    let format = format.or(profile.format).unwrap_or(Format::Markdown);
+    match output {
+        Some(path) if dry_run => print_dry_write(&path, &renderer.render(format, &input)?),
+        Some(path) => write_file(&path, |file| {
+            renderer.render_io(file, format, &input)?;
+            Ok(())
+        })?,
+        None => renderer.print(format, &input)?,
+    }
+
+    Ok(())
+}
+
+fn validate_inputs(lint: Lint) -> Result<()> {
+    let mut paths = lint.paths;
+
+    if paths.is_empty() {
+        paths.push(PathBuf::from("-"));
+    }
+
+    let config = Config::discover(&repo_root(Path::new(".")), lint.config.as_deref())?;
+    let profile = config.profile(lint.profile.as_deref())?;
+    let hash_length = lint.hash_length.or(profile.hash_length);
+
+    let mut problems = 0;
+
+    for path in &paths {
+        let (name, text) = if is_stdin(path) {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text)?;
+            ("standard input".to_string(), text)
+        } else {
+            let text = std::fs::read_to_string(path)
+                .map_err(|err| format!("{}: {}", path.display(), err))?;
+            (path.display().to_string(), text)
+        };
+
+        for diagnostic in validate::validate(&text, hash_length) {
+            println!(
+                "{}:{}:{}: {}",
+                name, diagnostic.line, diagnostic.column, diagnostic.problem
+            );
+            problems += 1;
+        }
+    }
+
+    match problems {
+        0 => Ok(()),
+        1 => Err("found 1 problem".into()),
+        n => Err(format!("found {} problems", n).into()),
+    }
+}
+
+fn write_hook(path: &Path, contents: &str, force: bool, dry_run: bool) -> Result<()> {
+    if let Ok(existing) = std::fs::read_to_string(path) {
+        if !existing.contains(HOOK_MARKER) && !force {
+            return Err(format!(
+                "{} already exists; use `--force` to overwrite it",
+                path.display()
+            )
+            .into());
+        }
+    }
+
+    if dry_run {
+        println!("Would install {}", path.display());
+        return Ok(());
+    }
+
+    std::fs::write(path, contents)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    eprintln!("Installed {}", path.display());
+
+    Ok(())
+}
+
+fn install_hooks(inst: InstallHooks, dry_run: bool) -> Result<()> {
+    let repo = Repository::discover(&inst.path)?;
+
+    let hooks = repo.hooks_dir()?;
+
+    if !dry_run {
+        std::fs::create_dir_all(&hooks)?;
+    }
+
+    write_hook(
+        &hooks.join("commit-msg"),
+        COMMIT_MSG_HOOK,
+        inst.force,
+        dry_run,
+    )?;
+    write_hook(
+        &hooks.join("prepare-commit-msg"),
+        PREPARE_COMMIT_MSG_HOOK,
+        inst.force,
+        dry_run,
+    )?;
+
+    let template = repo.git_dir().join("release-maker-commit-template");
+
+    if dry_run {
+        println!(
+            "Would install {} as the commit template",
+            template.display()
+        );
+        return Ok(());
+    }
+
+    std::fs::write(&template, COMMIT_TEMPLATE)?;
+    repo.set_config("commit.template", &template.to_string_lossy())?;
+
+    eprintln!("Installed {} as the commit template", template.display());
+
+    Ok(())
+}
+
+fn completions(comp: Completions) -> Result<()> {
+    let mut app = Cli::command();
+    let name = app.get_name().to_string();
+
+    clap_complete::generate(comp.shell, &mut app, name, &mut std::io::stdout());
+
+    Ok(())
+}
+
+/// Log to standard error at the level of the number of `-v` flags, unless `RUST_LOG` is defined.
+fn init_logging(verbose: u8) {
+    let level = match verbose {
+        0 => "warn",
+        1 => "info",
+        2 => "debug",
+        _ => "trace",
+    };
+
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(format!("release_maker={}", level)));
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .without_time()
+        .init();
+}
+
+fn main() {
+    let cli = Cli::parse();
+    init_logging(cli.verbose);
+    let dry_run = cli.dry_run;
+
+    let result = match cli.app {
+        App::Generate(gen) => generate(*gen, dry_run),
+        App::Retrieve(retr) => retrieve(*retr, dry_run),
+        App::Save(sav) => save(sav, dry_run),
+        App::History(hist) => history(hist),
+        App::Show(sh) => show(sh),
+        App::Latest(lat) => latest(lat),
+        App::InstallHooks(inst) => install_hooks(inst, dry_run),
+        App::Report(rep) => report(rep),
+        App::Publish(publ) => publish(publ, dry_run),
+        App::Changelog(cl) => update_changelog(cl, dry_run),
+        App::Version(ver) => next_version(ver, dry_run),
+        App::Run(run) => run_all(*run, dry_run),
+        App::Lint(lint) => validate_inputs(lint),
+        App::Completions(comp) => completions(comp),
+    };
 
-    match app {
-        App::Generate(gen) => generate(gen),
-        App::Retrieve(retr) => retrieve(retr),
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
     }
 }
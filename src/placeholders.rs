@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// Describes an error when substituting placeholders in a text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlaceholderError {
+    /// A `{{` was not followed by a closing `}}`.
+    Unterminated,
+    /// The environment variable of an `{{ env.NAME }}` placeholder is not set,
+    /// or is not valid unicode.
+    MissingEnv(String),
+    /// No variable was defined with the name of the placeholder.
+    UnknownVariable(String),
+}
+
+impl fmt::Display for PlaceholderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unterminated => f.write_str("placeholder is missing its closing `}}`"),
+            Self::MissingEnv(name) => write!(f, "environment variable `{}` is not set", name),
+            Self::UnknownVariable(name) => write!(
+                f,
+                "variable `{}` is not defined; define it with `--var {}=<value>`",
+                name, name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PlaceholderError {}
+
+/// Parse a `key=value` pair for the `--var` flag.
+pub fn parse_var(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected `key=value`, got `{}`", s)),
+    }
+}
+
+/// Substitutes `{{ name }}` and `{{ env.NAME }}` placeholders in texts.
+///
+/// `{{ name }}` is replaced with the value of a user-defined variable,
+/// while `{{ env.NAME }}` is replaced with the value of the `NAME` environment variable.
+#[derive(Debug, Default, Clone)]
+pub struct Placeholders {
+    vars: HashMap<String, String>,
+}
+
+impl Placeholders {
+    /// Create the substitutions from a list of user-defined variables.
+    pub fn new<I>(vars: I) -> Self
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        Self {
+            vars: vars.into_iter().collect(),
+        }
+    }
+
+    /// Define a variable, replacing its previous value.
+    pub fn set<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.vars.insert(key.into(), value.into());
+    }
+
+    fn lookup(&self, name: &str) -> Result<String, PlaceholderError> {
+        match name.strip_prefix("env.") {
+            Some(env) => {
+                std::env::var(env).map_err(|_| PlaceholderError::MissingEnv(env.to_string()))
+            }
+            None => self
+                .vars
+                .get(name)
+                .cloned()
+                .ok_or_else(|| PlaceholderError::UnknownVariable(name.to_string())),
+        }
+    }
+
+    /// Replace all placeholders in the text with their values.
+    pub fn substitute(&self, text: &str) -> Result<String, PlaceholderError> {
+        let mut res = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = rest.find("{{") {
+            res.push_str(&rest[..start]);
+            rest = &rest[start + 2..];
+
+            let end = rest.find("}}").ok_or(PlaceholderError::Unterminated)?;
+            res.push_str(&self.lookup(rest[..end].trim())?);
+            rest = &rest[end + 2..];
+        }
+
+        res.push_str(rest);
+
+        Ok(res)
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+use std::io::IsTerminal;
+
+/// The escape sequences that text is styled with.
+struct Style {
+    bold: &'static str,
+    normal: &'static str,
+    underline: &'static str,
+    no_underline: &'static str,
+    magenta: &'static str,
+    blue: &'static str,
+    cyan: &'static str,
+    default_color: &'static str,
+    reset: &'static str,
+}
+
+const COLORED: Style = Style {
+    bold: "\x1b[1m",
+    normal: "\x1b[22m",
+    underline: "\x1b[4m",
+    no_underline: "\x1b[24m",
+    magenta: "\x1b[35m",
+    blue: "\x1b[34m",
+    cyan: "\x1b[36m",
+    default_color: "\x1b[39m",
+    reset: "\x1b[0m",
+};
+
+const PLAIN: Style = Style {
+    bold: "",
+    normal: "",
+    underline: "",
+    no_underline: "",
+    magenta: "",
+    blue: "",
+    cyan: "",
+    default_color: "",
+    reset: "",
+};
+
+/// Returns whether the preview should be colored: if standard output is a terminal, and
+/// colors are not disabled by the `NO_COLOR` environment variable.
+pub fn use_color() -> bool {
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+}
+
+/// Parse the definition of a reference-style link, such as `[@name]: https://github.com/name`,
+/// returning its label.
+fn definition(line: &str) -> Option<&str> {
+    let (label, url) = line.strip_prefix('[')?.split_once("]: ")?;
+
+    if label.is_empty() || url.is_empty() || url.contains(char::is_whitespace) {
+        return None;
+    }
+
+    Some(label)
+}
+
+/// Returns the index of the first unescaped occurrence of `c` in `text`.
+fn find_unescaped(text: &str, c: char) -> Option<usize> {
+    let mut escaped = false;
+
+    for (i, ch) in text.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            _ if ch == c => return Some(i),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Render the inline Markdown of `text`: bold text, code spans, links, and escaped characters.
+///
+/// Links are written as their text, as the terminal cannot follow them, and reference-style links
+/// only if they are defined in `labels`.
+fn write_inline(res: &mut String, text: &str, labels: &HashSet<&str>, style: &Style) {
+    let mut rest = text;
+    let mut bold = false;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(escaped) = rest.strip_prefix('\\').and_then(|r| r.chars().next()) {
+            res.push(escaped);
+            rest = &rest[1 + escaped.len_utf8()..];
+            continue;
+        }
+
+        if let Some(after) = rest.strip_prefix("**") {
+            res.push_str(if bold { style.normal } else { style.bold });
+            bold = !bold;
+            rest = after;
+            continue;
+        }
+
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                res.push_str(style.cyan);
+                res.push_str(&rest[1..end + 1]);
+                res.push_str(style.default_color);
+                rest = &rest[end + 2..];
+                continue;
+            }
+        }
+
+        if c == '[' {
+            if let Some(end) = find_unescaped(&rest[1..], ']') {
+                let label = &rest[1..end + 1];
+                let after = &rest[end + 2..];
+
+                // An inline link, such as `[text](url)`.
+                if let Some(target) = after.strip_prefix('(') {
+                    if let Some(close) = target.find(')') {
+                        res.push_str(style.blue);
+                        res.push_str(style.underline);
+                        write_inline(res, label, labels, style);
+                        res.push_str(style.no_underline);
+                        res.push_str(style.default_color);
+                        rest = &target[close + 1..];
+                        continue;
+                    }
+                }
+
+                if labels.contains(label) {
+                    res.push_str(style.blue);
+                    write_inline(res, label, labels, style);
+                    res.push_str(style.default_color);
+                    rest = after;
+                    continue;
+                }
+            }
+        }
+
+        res.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    if bold {
+        res.push_str(style.normal);
+    }
+}
+
+/// Render Markdown, such as the output of [`generate_msg`], with colors and bold text for
+/// reading in a terminal.
+///
+/// Headings are written in bold, list items with bullets, and links as their text. The definitions
+/// of reference-style links are omitted. Without `color`, such as when the output is not a
+/// terminal, the text is written without escape sequences.
+///
+/// [`generate_msg`]: ../release/fn.generate_msg.html
+pub fn render(markdown: &str, color: bool) -> String {
+    let style = if color { &COLORED } else { &PLAIN };
+    let labels = markdown
+        .lines()
+        .filter_map(definition)
+        .collect::<HashSet<_>>();
+
+    let mut res = String::with_capacity(markdown.len());
+    let mut blank = false;
+
+    for line in markdown.lines() {
+        if definition(line).is_some() {
+            continue;
+        }
+
+        // Omitting the definitions leaves consecutive empty lines, which are collapsed.
+        if line.trim().is_empty() {
+            if !blank && !res.is_empty() {
+                res.push('\n');
+            }
+
+            blank = true;
+            continue;
+        }
+
+        blank = false;
+
+        let level = line.chars().take_while(|&c| c == '#').count();
+
+        if let Some(heading) = line[level..].strip_prefix(' ').filter(|_| level > 0) {
+            res.push_str(style.bold);
+
+            if level <= 2 {
+                res.push_str(style.magenta);
+                res.push_str(style.underline);
+            }
+
+            write_inline(&mut res, heading, &labels, style);
+            res.push_str(style.reset);
+        } else if let Some(item) = line.strip_prefix("- ") {
+            res.push_str("  • ");
+            write_inline(&mut res, item, &labels, style);
+        } else {
+            write_inline(&mut res, line, &labels, style);
+        }
+
+        res.push('\n');
+    }
+
+    // As do the definitions at the end.
+    if res.ends_with("\n\n") {
+        res.pop();
+    }
+
+    res
+}
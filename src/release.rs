@@ -1,10 +1,19 @@
+use crate::error::Error;
+use crate::git::Repository;
+use crate::gitea;
+use crate::locale::Strings;
+
+use clap::ArgEnum;
+use indexmap::{IndexMap, IndexSet};
 use serde::de::{Error as DeError, SeqAccess, Visitor};
 use serde::ser::SerializeSeq;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt;
+use std::io;
 use std::marker::PhantomData;
 
 /// A utility for deserialization of an arbitrary amount of `T`, expecting at least one item.
@@ -38,6 +47,7 @@ where
 impl<'de, T> Deserialize<'de> for OneOrMore<T>
 where
     T: TryFrom<String>,
+    T::Error: fmt::Display,
 {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         struct V<T>(PhantomData<T>);
@@ -45,6 +55,7 @@ where
         impl<'de, T> Visitor<'de> for V<T>
         where
             T: TryFrom<String>,
+            T::Error: fmt::Display,
         {
             type Value = Vec<T>;
 
@@ -55,7 +66,7 @@ where
             fn visit_str<E: DeError>(self, v: &str) -> Result<Self::Value, E> {
                 let item = match T::try_from(v.to_string()) {
                     Ok(item) => item,
-                    Err(_) => return Err(E::custom("failed to parse from string")),
+                    Err(err) => return Err(E::custom(err)),
                 };
 
                 Ok(vec![item])
@@ -67,13 +78,15 @@ where
                 while let Some(elem) = seq.next_element::<String>()? {
                     let item = match T::try_from(elem) {
                         Ok(item) => item,
-                        Err(_) => return Err(A::Error::custom("failed to parse from string")),
+                        Err(err) => return Err(A::Error::custom(err)),
                     };
 
                     v.push(item);
                 }
 
-                assert!(v.len() >= 1, "expected at least one string");
+                if v.is_empty() {
+                    return Err(A::Error::invalid_length(0, &self));
+                }
 
                 Ok(v)
             }
@@ -84,6 +97,9 @@ where
 }
 
 /// Describes a Github author by their name.
+///
+/// Authors that cannot be resolved to a Github account may be described by
+/// their name and email, as `Name <email>`.
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct Author(String);
 
@@ -97,13 +113,179 @@ impl Author {
         Self(name.into())
     }
 
+    /// Create a new Author from the name and email of a Git identity.
+    ///
+    /// If the name does not look like a username, the email is retained
+    /// as `Name <email>`.
+    pub fn from_identity(name: &str, email: &str) -> Self {
+        let author = Self::new(name);
+
+        if author.is_username() || email.is_empty() {
+            author
+        } else {
+            Self(format!("{} <{}>", name, email))
+        }
+    }
+
     /// Access the author's name.
     #[inline]
     pub fn name(&self) -> &str {
-        &self.0
+        match self.0.find(" <") {
+            Some(end) if self.0.ends_with('>') => &self.0[..end],
+            _ => &self.0,
+        }
+    }
+
+    /// Access the author's email, if it was specified as `Name <email>`.
+    pub fn email(&self) -> Option<&str> {
+        let start = self.0.find(" <")?;
+        self.0[start + 2..].strip_suffix('>')
+    }
+
+    /// Returns whether the author is described by a username, as opposed to
+    /// a full name or an email.
+    pub fn is_username(&self) -> bool {
+        if self.email().is_some() {
+            return false;
+        }
+
+        let name = self.0.strip_suffix("[bot]").unwrap_or(&self.0);
+
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
     }
 }
 
+/// Describes the host of a repository, which determines the links to its commits and pull requests.
+#[derive(ArgEnum, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Forge {
+    /// Github, linking to `/commit/<hash>` and `/pull/<number>`.
+    Github,
+    /// GitLab, linking to `/-/commit/<hash>` and `/-/merge_requests/<number>`.
+    Gitlab,
+    /// Gitea and its fork Forgejo, such as Codeberg, linking to `/commit/<hash>`
+    /// and `/pulls/<number>`.
+    Gitea,
+    /// Azure DevOps, linking to `/commit/<hash>` and `/pullrequest/<number>`
+    /// of repositories such as `https://dev.azure.com/org/project/_git/repo`.
+    ///
+    /// As Azure DevOps has no profiles by the names of users, authors are not linked
+    /// unless the URL to their profiles is defined.
+    Azure,
+}
+
+impl Forge {
+    /// Detect the forge from the URL to a repository, by whether its host mentions GitLab,
+    /// or Gitea, Forgejo, or Codeberg, or is Azure DevOps.
+    pub fn detect(url: &str) -> Self {
+        let host = host_url(url);
+
+        if host.ends_with("dev.azure.com") || host.ends_with(".visualstudio.com") {
+            Forge::Azure
+        } else if host.contains("gitlab") {
+            Forge::Gitlab
+        } else if ["gitea", "forgejo", "codeberg"]
+            .iter()
+            .any(|name| host.contains(name))
+        {
+            Forge::Gitea
+        } else {
+            Forge::Github
+        }
+    }
+}
+
+impl std::str::FromStr for Forge {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "github" => Ok(Self::Github),
+            "gitlab" => Ok(Self::Gitlab),
+            "gitea" => Ok(Self::Gitea),
+            "azure" => Ok(Self::Azure),
+            _ => Err(format!(
+                "unknown forge `{}`, expected `github`, `gitlab`, `gitea`, or `azure`",
+                s
+            )),
+        }
+    }
+}
+
+/// Templates of the URLs to the commits, pull requests, comparisons, and tags of a repository,
+/// such as `{repo_url}/commit/{hash}`, overriding the conventions of its forge.
+///
+/// `{repo_url}` is replaced with the URL to the repository in each template.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct UrlTemplates {
+    /// The template of the URL to a commit, where `{hash}` is replaced with its full hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_url: Option<String>,
+    /// The template of the URL to a pull request, where `{number}` is replaced with its number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pull_request_url: Option<String>,
+    /// The template of the URL to the comparison of two revisions, where `{from}` and `{to}`
+    /// are replaced with the revisions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compare_url: Option<String>,
+    /// The template of the URL to a tag, where `{tag}` is replaced with its name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_url: Option<String>,
+}
+
+impl UrlTemplates {
+    /// Fall back to the templates of `other` for templates absent from these.
+    pub fn or(self, other: UrlTemplates) -> UrlTemplates {
+        UrlTemplates {
+            commit_url: self.commit_url.or(other.commit_url),
+            pull_request_url: self.pull_request_url.or(other.pull_request_url),
+            compare_url: self.compare_url.or(other.compare_url),
+            tag_url: self.tag_url.or(other.tag_url),
+        }
+    }
+}
+
+/// Describes how to render authors that are not described by a username.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthorFallback {
+    /// Render the author's name as plain text.
+    #[default]
+    Text,
+    /// Render the author's name as a `mailto:` link, if their email is known.
+    Mailto,
+    /// Render the author's name as a link to their profile, as if it was a username.
+    Link,
+}
+
+impl std::str::FromStr for AuthorFallback {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "mailto" => Ok(Self::Mailto),
+            "link" => Ok(Self::Link),
+            _ => Err(format!(
+                "unknown author fallback `{}`, expected `text`, `mailto` or `link`",
+                s
+            )),
+        }
+    }
+}
+
+/// Describes where the name of an author links to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthorLink {
+    /// The URL to the author's profile.
+    Profile(String),
+    /// A `mailto:` URL to the author's email.
+    Mail(String),
+}
+
 impl fmt::Display for Author {
     /// Format the author to display the second part of reference-style link of a Github mention to them.
     #[inline]
@@ -112,36 +294,55 @@ impl fmt::Display for Author {
     }
 }
 
-impl TryFrom<String> for Author {
-    type Error = std::convert::Infallible;
+impl From<String> for Author {
+    #[inline]
+    fn from(s: String) -> Self {
+        Self::new(s)
+    }
+}
 
+impl From<&str> for Author {
     #[inline]
-    fn try_from(s: String) -> Result<Self, Self::Error> {
-        Ok(Self::new(s))
+    fn from(s: &str) -> Self {
+        Self::new(s)
     }
 }
 
+/// The minimum length of an abbreviated commit hash.
+pub const MIN_HASH_LENGTH: usize = 4;
+
+/// The length that commit hashes are abbreviated to, unless the release defines another.
+pub const DEFAULT_HASH_LENGTH: usize = 7;
+
+/// Returns whether a hash consists of hexadecimal digits only, as commit hashes do.
+pub fn is_hex_hash(hash: &str) -> bool {
+    hash.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
 /// Describes a Git commit by its hash.
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String")]
 pub struct Commit(String);
 
 impl Commit {
     /// Create a new commit with its hash.
     ///
-    /// # Panics
-    /// A panic is incurred if:
-    /// - the passed hash is shorter than 7 characters.
+    /// # Errors
+    /// An error is returned if:
+    /// - the passed hash is shorter than [`MIN_HASH_LENGTH`] characters, or
+    /// - the passed hash contains characters other than hexadecimal digits.
     #[inline]
-    pub fn new<I>(hash: I) -> Self
+    pub fn new<I>(hash: I) -> Result<Self, CommitConversionError>
     where
         I: Into<String>,
     {
         let hash = hash.into();
-        assert!(
-            hash.len() >= 7,
-            "commit hashes must not be shorter than 7 characters"
-        );
-        Self(hash)
+
+        if hash.len() < MIN_HASH_LENGTH || !is_hex_hash(&hash) {
+            return Err(CommitConversionError(hash));
+        }
+
+        Ok(Self(hash))
     }
 
     /// Access the commit hash.
@@ -149,6 +350,18 @@ impl Commit {
     pub fn hash(&self) -> &str {
         &self.0
     }
+
+    /// Access the hash, abbreviated to its first seven characters for legibility.
+    #[inline]
+    pub fn short(&self) -> &str {
+        self.abbreviate(DEFAULT_HASH_LENGTH)
+    }
+
+    /// Access the hash, abbreviated to its first `len` characters.
+    #[inline]
+    pub fn abbreviate(&self, len: usize) -> &str {
+        &self.0[..self.0.len().min(len)]
+    }
 }
 
 /// Describes an error when trying to convert to a [`Commit`] from a [`String`].
@@ -164,7 +377,11 @@ pub struct CommitConversionError(
 impl fmt::Display for CommitConversionError {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Display::fmt("commit hashes must not be shorter than 7 characters", f)
+        write!(
+            f,
+            "`{}` is not a commit hash of at least {} hexadecimal digits",
+            self.0, MIN_HASH_LENGTH
+        )
     }
 }
 
@@ -177,17 +394,14 @@ impl TryFrom<String> for Commit {
     ///
     /// # Errors
     /// An error is returned if:
-    /// - the passed [`String`] is shorter than 7 characters
+    /// - the passed [`String`] is shorter than [`MIN_HASH_LENGTH`] characters, or
+    /// - the passed [`String`] contains characters other than hexadecimal digits.
     ///
     /// [`Commit`]: struct.Commit.html
     /// [`String`]: std::string::String
     #[inline]
     fn try_from(s: String) -> Result<Self, Self::Error> {
-        if s.len() < 7 {
-            return Err(CommitConversionError(s));
-        }
-
-        Ok(Self::new(s))
+        Self::new(s)
     }
 }
 
@@ -196,7 +410,40 @@ impl fmt::Display for Commit {
     /// Only the first seven characters of the hash are outputted, for legibility.
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "[c:{}]", &self.hash()[..7])
+        write!(f, "[c:{}]", self.short())
+    }
+}
+
+/// Describes a Github pull request by its number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PullRequest(pub u64);
+
+impl PullRequest {
+    /// Split a trailing pull request reference, such as `(#123)`, or `(!123)` as GitLab refers
+    /// to merge requests, from the name of a change.
+    ///
+    /// Returns the name without the reference, and the pull request if there was one.
+    pub fn split(name: &str) -> (&str, Option<Self>) {
+        let parse = || {
+            let rest = name.trim_end().strip_suffix(')')?;
+            let start = rest.rfind('(')?;
+            let number = rest[start + 1..].strip_prefix(['#', '!'])?.parse().ok()?;
+
+            Some((rest[..start].trim_end(), PullRequest(number)))
+        };
+
+        match parse() {
+            Some((name, pr)) => (name, Some(pr)),
+            None => (name, None),
+        }
+    }
+}
+
+impl fmt::Display for PullRequest {
+    /// Format the pull request to display the second part of reference-style link to it.
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[#{}]", self.0)
     }
 }
 
@@ -205,29 +452,170 @@ impl fmt::Display for Commit {
 /// The first field describes the location of the change - category.<br>
 /// The second field expresses the name of the change - name.<br>
 /// The third field specifies the author(s) of the change that participated - authors.<br>
-/// The fourth field tells the commit(s) of the change - commits.
+/// The fourth field tells the commit(s) of the change - commits.<br>
+/// The optional fifth field holds the full text of the change, if the name is shortened - description.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Change(
     pub String,
     pub String,
     pub OneOrMore<Author>,
     pub OneOrMore<Commit>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub Option<String>,
 );
 
 impl Change {
     /// Create a new Change with a category, a name, a single author, and a single commit.
-    pub fn new<A, B, C, D>(category: A, name: B, author: C, commit: D) -> Self
+    ///
+    /// # Errors
+    /// An error is returned if the hash of the commit is invalid, as with [`Commit::new`].
+    pub fn new<A, B, C, D>(
+        category: A,
+        name: B,
+        author: C,
+        commit: D,
+    ) -> Result<Self, CommitConversionError>
     where
         A: Into<String>,
         B: Into<String>,
-        C: Into<String>,
+        C: Into<Author>,
         D: Into<String>,
     {
-        Self(
+        Ok(Self(
             category.into(),
             name.into(),
-            OneOrMore(vec![Author::new(author)]),
-            OneOrMore(vec![Commit::new(commit)]),
+            OneOrMore(vec![author.into()]),
+            OneOrMore(vec![Commit::new(commit)?]),
+            None,
+        ))
+    }
+
+    /// Return the pull request of the change, as referenced at the end of its name (e.g. `(#123)`).
+    pub fn pull_request(&self) -> Option<PullRequest> {
+        PullRequest::split(&self.1).1
+    }
+}
+
+/// Append changes to a section, joining those with the category and name of an existing change
+/// into it, without duplicating its authors and commits.
+fn merge_changes(changes: &mut Vec<Change>, others: Vec<Change>) {
+    for other in others {
+        let existing = changes
+            .iter_mut()
+            .find(|change| change.0 == other.0 && change.1 == other.1);
+
+        let change = match existing {
+            Some(change) => change,
+            None => {
+                changes.push(other);
+                continue;
+            }
+        };
+
+        for author in (other.2).0 {
+            if !(change.2).0.contains(&author) {
+                (change.2).0.push(author);
+            }
+        }
+
+        for commit in (other.3).0 {
+            if !(change.3).0.contains(&commit) {
+                (change.3).0.push(commit);
+            }
+        }
+
+        if change.4.is_none() {
+            change.4 = other.4;
+        }
+    }
+}
+
+/// Describes a section of a [`Release`].
+///
+/// [`Release`]: struct.Release.html
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Section {
+    Breaking,
+    Added,
+    Changed,
+    Deprecated,
+    Fixed,
+    Removed,
+    Security,
+}
+
+impl Section {
+    /// All sections, in the order they are rendered.
+    pub const ALL: [Section; 7] = [
+        Section::Breaking,
+        Section::Added,
+        Section::Changed,
+        Section::Deprecated,
+        Section::Fixed,
+        Section::Removed,
+        Section::Security,
+    ];
+
+    /// The heading of the section, as it is rendered.
+    pub fn heading(self) -> &'static str {
+        match self {
+            Section::Breaking => "Breaking changes",
+            Section::Added => "Added",
+            Section::Changed => "Changed",
+            Section::Deprecated => "Deprecated",
+            Section::Fixed => "Fixed",
+            Section::Removed => "Removed",
+            Section::Security => "Security",
+        }
+    }
+
+    /// The name of the section, as it appears in the input.
+    pub fn name(self) -> &'static str {
+        match self {
+            Section::Breaking => "breaking",
+            Section::Added => "added",
+            Section::Changed => "changed",
+            Section::Deprecated => "deprecated",
+            Section::Fixed => "fixed",
+            Section::Removed => "removed",
+            Section::Security => "security",
+        }
+    }
+}
+
+/// Statistics of a [`Release`].
+///
+/// [`Release`]: struct.Release.html
+#[derive(Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of changes.
+    pub changes: usize,
+    /// The number of unique authors.
+    pub contributors: usize,
+    /// The number of unique commits.
+    pub commits: usize,
+}
+
+impl fmt::Display for Stats {
+    /// Format the statistics as a sentence, such as "42 changes from 13 contributors across 97 commits".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn plural(n: usize) -> &'static str {
+            if n == 1 {
+                ""
+            } else {
+                "s"
+            }
+        }
+
+        write!(
+            f,
+            "{} change{} from {} contributor{} across {} commit{}",
+            self.changes,
+            plural(self.changes),
+            self.contributors,
+            plural(self.contributors),
+            self.commits,
+            plural(self.commits)
         )
     }
 }
@@ -237,46 +625,808 @@ impl Change {
 pub struct Release {
     /// The URL to the Github repository.
     pub repo_url: String,
+    /// The version of the release, such as `v1.2.3`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The date of the release, such as `2024-06-01`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// The title of the release, such as its codename.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The version of the previous release, such as `v1.1.0`.
+    ///
+    /// Along with the version, it links to the comparison of both releases.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_version: Option<String>,
+    /// The template of the URL to the profile of an author, where `{name}` is replaced
+    /// with the name of the author.
+    ///
+    /// If absent, the profile is assumed to be at the root of the host of the repository,
+    /// such as `https://github.com/{name}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_url: Option<String>,
+    /// How to render authors that are not described by a username.
+    ///
+    /// If absent, they are rendered as plain text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_fallback: Option<AuthorFallback>,
+    /// Templates of the URLs to commits, pull requests, comparisons, and tags, taking
+    /// precedence over the conventions of the forge.
+    #[serde(flatten)]
+    pub url_templates: UrlTemplates,
+    /// The host of the repository, which determines the links to commits and pull requests.
+    ///
+    /// If absent, it is detected from the URL to the repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forge: Option<Forge>,
+    /// Users that approved the changes of the release in reviews.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reviewers: Vec<Author>,
+    /// Authors whose first contribution to the repository is part of the release.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub new_contributors: Vec<NewContributor>,
+    /// Text written before the list of contributors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intro: Option<String>,
+    /// Text summarizing the release, written after the intro.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Text written after the lists of changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epilogue: Option<String>,
+    /// Changes that break compatibility, written before all other sections.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub breaking: Vec<Change>,
     /// Changes whose purpose was to add functionality.
     #[serde(default)]
     pub added: Vec<Change>,
     /// Changes whose purpose was to change existing functionality.
     #[serde(default)]
     pub changed: Vec<Change>,
+    /// Changes that mark functionality as deprecated, to be removed in a future release.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deprecated: Vec<Change>,
     /// Changes whose purpose was to fix existing functionality.
     #[serde(default)]
     pub fixed: Vec<Change>,
     /// Changes whose purpose was to remove existing functionality.
     #[serde(default)]
     pub removed: Vec<Change>,
+    /// Changes that fix vulnerabilities.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub security: Vec<Change>,
+    /// Sections beyond the built-in ones, such as `documentation` or `performance`, by their names.
+    ///
+    /// They are rendered after the built-in sections, in their order.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub sections: IndexMap<String, CustomSection>,
+    /// The names of sections in the order they are rendered, such as `["fixed", "added"]`.
+    ///
+    /// Sections that are not named follow in their usual order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub section_order: Vec<String>,
+    /// Headings of sections by their names, such as `Features` for `added`, overriding
+    /// their usual headings.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headings: BTreeMap<String, String>,
+    /// The number of characters that commit hashes are abbreviated to in the output.
+    ///
+    /// If absent, hashes are abbreviated to seven characters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_length: Option<usize>,
+    /// Whether categories and names of changes are written as they are in Markdown output,
+    /// such as to embed Markdown in them, rather than escaped.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub raw_names: bool,
+    /// The texts of the output besides the headings of sections, such as for translating them.
+    #[serde(default, skip_serializing_if = "Strings::is_empty")]
+    pub strings: Strings,
+    /// Changes of other repositories aggregated into the release, each written under its own heading.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<Component>,
+}
+
+/// An author whose first contribution to the repository is part of a [`Release`].
+///
+/// [`Release`]: struct.Release.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewContributor {
+    pub author: Author,
+    /// The first commit of the author.
+    pub commit: Commit,
+    /// The number of the pull request of the first commit, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pull_request: Option<u64>,
+}
+
+/// A section of a [`Release`] beyond the built-in ones.
+///
+/// [`Release`]: struct.Release.html
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct CustomSection {
+    /// The heading of the section, as it is rendered.
+    ///
+    /// If absent, the name of the section is capitalized, such as `Performance` for `performance`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+    /// The changes of the section.
+    #[serde(default)]
+    pub changes: Vec<Change>,
+}
+
+impl CustomSection {
+    /// The heading of the section with the `name`, as it is rendered.
+    pub fn heading<'a>(&'a self, name: &'a str) -> Cow<'a, str> {
+        if let Some(heading) = &self.heading {
+            return Cow::Borrowed(heading);
+        }
+
+        let mut chars = name.chars();
+
+        match chars.next() {
+            Some(first) => Cow::Owned(first.to_uppercase().chain(chars).collect()),
+            None => Cow::Borrowed(name),
+        }
+    }
+}
+
+/// A built-in or custom section of a [`Release`], with its changes.
+///
+/// [`Release`]: struct.Release.html
+#[derive(Debug, Clone)]
+pub struct SectionChanges<'a> {
+    /// The name of the section, as it appears in the input.
+    pub name: &'a str,
+    /// The heading of the section, as it is rendered.
+    pub heading: Cow<'a, str>,
+    pub changes: &'a [Change],
+}
+
+/// The changes of another repository, aggregated into a [`Release`].
+///
+/// The links to its commits and pull requests are rooted at its own `repo_url`.
+///
+/// [`Release`]: struct.Release.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Component {
+    /// The name of the component, such as `core`.
+    pub name: String,
+    #[serde(flatten)]
+    pub release: Release,
 }
 
 impl Release {
-    fn iter(&self) -> impl Iterator<Item = &Change> + '_ {
-        self.added
+    /// Return an iterator of all changes of the whole release.
+    pub fn iter(&self) -> impl Iterator<Item = &Change> + '_ {
+        Section::ALL
             .iter()
-            .chain(self.changed.iter())
-            .chain(self.fixed.iter())
-            .chain(self.removed.iter())
+            .flat_map(move |&section| self.section(section).iter())
+            .chain(
+                self.sections
+                    .values()
+                    .flat_map(|custom| custom.changes.iter()),
+            )
+    }
+
+    /// Return an iterator of all changes of the whole release, mutably.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Change> + '_ {
+        self.breaking
+            .iter_mut()
+            .chain(self.added.iter_mut())
+            .chain(self.changed.iter_mut())
+            .chain(self.deprecated.iter_mut())
+            .chain(self.fixed.iter_mut())
+            .chain(self.removed.iter_mut())
+            .chain(self.security.iter_mut())
+            .chain(
+                self.sections
+                    .values_mut()
+                    .flat_map(|custom| custom.changes.iter_mut()),
+            )
+    }
+
+    /// Return the sections of the release, in the order they are rendered.
+    ///
+    /// See [`sections_of`].
+    ///
+    /// [`sections_of`]: #method.sections_of
+    pub fn sections(&self) -> Vec<SectionChanges<'_>> {
+        self.sections_of(self)
     }
 
-    /// Return all unique authors of the whole release.
-    pub fn get_authors(&self) -> Vec<Author> {
+    /// Return the sections of `part`, which is either this release or one of its components,
+    /// in the order they are rendered and with their headings as defined by this release.
+    ///
+    /// Unless ordered by [`section_order`], the built-in sections are followed by the custom
+    /// sections.
+    ///
+    /// [`section_order`]: #structfield.section_order
+    pub fn sections_of<'a>(&'a self, part: &'a Release) -> Vec<SectionChanges<'a>> {
+        let builtin = Section::ALL.iter().map(move |&section| SectionChanges {
+            name: section.name(),
+            heading: Cow::Borrowed(section.heading()),
+            changes: part.section(section),
+        });
+
+        let custom = part.sections.iter().map(|(name, custom)| SectionChanges {
+            name,
+            heading: custom.heading(name),
+            changes: &custom.changes,
+        });
+
+        let mut sections = builtin
+            .chain(custom)
+            .map(|mut section| {
+                if let Some(heading) = self.headings.get(section.name) {
+                    section.heading = Cow::Borrowed(heading);
+                }
+
+                section
+            })
+            .collect::<Vec<_>>();
+
+        // Sorting is stable, so that the sections that are not named keep their order.
+        sections.sort_by_key(|section| {
+            self.section_order
+                .iter()
+                .position(|name| name == section.name)
+                .unwrap_or(self.section_order.len())
+        });
+
+        sections
+    }
+
+    /// Return the heading of the release made of its version, date, and title, such as
+    /// `v1.2.3 — 2024-06-01 "Codename"`, or `None` if it has none of them.
+    pub fn heading(&self) -> Option<String> {
+        let mut heading = [&self.version, &self.date]
+            .iter()
+            .filter_map(|part| part.as_deref())
+            .collect::<Vec<_>>()
+            .join(" — ");
+
+        match &self.title {
+            Some(title) if heading.is_empty() => heading.push_str(title),
+            Some(title) => heading.push_str(&format!(" \"{}\"", title)),
+            None if heading.is_empty() => return None,
+            None => {}
+        }
+
+        Some(heading)
+    }
+
+    /// Access the changes of a section.
+    pub fn section(&self, section: Section) -> &[Change] {
+        match section {
+            Section::Breaking => &self.breaking,
+            Section::Added => &self.added,
+            Section::Changed => &self.changed,
+            Section::Deprecated => &self.deprecated,
+            Section::Fixed => &self.fixed,
+            Section::Removed => &self.removed,
+            Section::Security => &self.security,
+        }
+    }
+
+    /// Access the changes of a section mutably.
+    pub fn section_mut(&mut self, section: Section) -> &mut Vec<Change> {
+        match section {
+            Section::Breaking => &mut self.breaking,
+            Section::Added => &mut self.added,
+            Section::Changed => &mut self.changed,
+            Section::Deprecated => &mut self.deprecated,
+            Section::Fixed => &mut self.fixed,
+            Section::Removed => &mut self.removed,
+            Section::Security => &mut self.security,
+        }
+    }
+
+    /// Normalize the URLs to the repositories of the release and its components.
+    ///
+    /// See [`normalize_url`].
+    ///
+    /// [`normalize_url`]: fn.normalize_url.html
+    pub fn normalize_urls(&mut self) -> Result<(), Error> {
+        self.repo_url = normalize_url(&self.repo_url)?;
+
+        for component in &mut self.components {
+            component.release.normalize_urls()?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that every change of the release and its components has a category, as the output
+    /// requires.
+    pub fn check_categories(&self) -> Result<(), Error> {
+        let parts = std::iter::once(self).chain(self.components.iter().map(|c| &c.release));
+
+        for part in parts {
+            for section in self.sections_of(part) {
+                let empty = section.changes.iter().find(|change| change.0.is_empty());
+
+                if let Some(Change(_, name, ..)) = empty {
+                    return Err(Error::EmptyCategory {
+                        section: section.name.to_string(),
+                        name: name.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return an iterator of all changes of the whole release, including those of its components.
+    pub fn iter_all(&self) -> impl Iterator<Item = &Change> + '_ {
         self.iter()
-            .flat_map(|Change(_, _, OneOrMore(authors), _)| authors.iter().cloned())
-            .collect::<HashSet<Author>>()
-            .into_iter()
+            .chain(self.components.iter().flat_map(|c| c.release.iter()))
+    }
+
+    /// Merge the changes of another release into this one.
+    ///
+    /// The changes of each section are appended after the existing changes of the same section.
+    /// A change with the same category and name as an existing change is joined with it instead,
+    /// adding the authors and commits it lacks. Components are merged with the components
+    /// of the same name, and texts and options absent from this release are taken from the other.
+    pub fn merge(&mut self, other: Release) {
+        merge_changes(&mut self.breaking, other.breaking);
+        merge_changes(&mut self.added, other.added);
+        merge_changes(&mut self.changed, other.changed);
+        merge_changes(&mut self.deprecated, other.deprecated);
+        merge_changes(&mut self.fixed, other.fixed);
+        merge_changes(&mut self.removed, other.removed);
+        merge_changes(&mut self.security, other.security);
+
+        for (name, custom) in other.sections {
+            match self.sections.get_mut(&name) {
+                Some(existing) => {
+                    existing.heading = existing.heading.take().or(custom.heading);
+                    merge_changes(&mut existing.changes, custom.changes);
+                }
+                None => {
+                    self.sections.insert(name, custom);
+                }
+            }
+        }
+
+        self.version = self.version.take().or(other.version);
+        self.date = self.date.take().or(other.date);
+        self.title = self.title.take().or(other.title);
+        self.previous_version = self.previous_version.take().or(other.previous_version);
+        self.hash_length = self.hash_length.or(other.hash_length);
+        self.raw_names |= other.raw_names;
+        self.author_url = self.author_url.take().or(other.author_url);
+        self.author_fallback = self.author_fallback.or(other.author_fallback);
+        self.url_templates = std::mem::take(&mut self.url_templates).or(other.url_templates);
+        self.forge = self.forge.or(other.forge);
+        self.intro = self.intro.take().or(other.intro);
+        self.summary = self.summary.take().or(other.summary);
+        self.epilogue = self.epilogue.take().or(other.epilogue);
+
+        for component in other.components {
+            match self
+                .components
+                .iter_mut()
+                .find(|c| c.name == component.name)
+            {
+                Some(existing) => existing.release.merge(component.release),
+                None => self.components.push(component),
+            }
+        }
+
+        for reviewer in other.reviewers {
+            if !self.reviewers.contains(&reviewer) {
+                self.reviewers.push(reviewer);
+            }
+        }
+
+        for contributor in other.new_contributors {
+            if !self
+                .new_contributors
+                .iter()
+                .any(|c| c.author == contributor.author)
+            {
+                self.new_contributors.push(contributor);
+            }
+        }
+    }
+
+    /// Return the URL to the profile of an author.
+    pub fn get_author_url(&self, author: &Author) -> String {
+        match &self.author_url {
+            Some(template) => template.replace("{name}", author.name()),
+            None => format!("{}/{}", self.instance_url(), author.name()),
+        }
+    }
+
+    /// Expand a template of a URL, replacing `{repo_url}` and the `{name}` of each of the `vars`.
+    fn expand_url(&self, template: &str, vars: &[(&str, &str)]) -> String {
+        let mut url = template.replace("{repo_url}", &self.repo_url);
+
+        for (name, value) in vars {
+            url = url.replace(&format!("{{{}}}", name), value);
+        }
+
+        url
+    }
+
+    /// Return the scheme and host of the URL to the repository, such as `https://github.com`.
+    pub fn host_url(&self) -> &str {
+        host_url(&self.repo_url)
+    }
+
+    /// Return the URL of the instance of the forge hosting the repository, such as
+    /// `https://github.com`, or `https://example.com/gitea` for a Gitea instance served
+    /// under a path.
+    pub fn instance_url(&self) -> &str {
+        match self.forge() {
+            Forge::Gitea => gitea::split_repo_url(&self.repo_url)
+                .map_or_else(|| self.host_url(), |(instance, _)| instance),
+            _ => self.host_url(),
+        }
+    }
+
+    /// Return the host of the repository, as defined or detected from its URL.
+    pub fn forge(&self) -> Forge {
+        self.forge.unwrap_or_else(|| Forge::detect(&self.repo_url))
+    }
+
+    /// Return the URL to a commit of the repository.
+    pub fn commit_url(&self, hash: &str) -> String {
+        if let Some(template) = &self.url_templates.commit_url {
+            return self.expand_url(template, &[("hash", hash)]);
+        }
+
+        match self.forge() {
+            Forge::Github | Forge::Gitea | Forge::Azure => {
+                format!("{}/commit/{}", self.repo_url, hash)
+            }
+            Forge::Gitlab => format!("{}/-/commit/{}", self.repo_url, hash),
+        }
+    }
+
+    /// Return the number of characters that commit hashes are abbreviated to in the output.
+    pub fn hash_length(&self) -> usize {
+        self.hash_length.unwrap_or(DEFAULT_HASH_LENGTH)
+    }
+
+    /// Return the hash of a commit, abbreviated as it is rendered.
+    pub fn short_hash<'a>(&self, commit: &'a Commit) -> &'a str {
+        commit.abbreviate(self.hash_length())
+    }
+
+    /// Return a category or name of a change as it is written in Markdown.
+    pub fn markdown_text<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.raw_names {
+            Cow::Borrowed(text)
+        } else {
+            escape_markdown(text)
+        }
+    }
+
+    /// Return the label of the reference-style link to a commit, such as `[c:1a2b3c4]`,
+    /// or `[core/c:1a2b3c4]` with the `prefix` of a component.
+    fn commit_label(&self, commit: &Commit, prefix: &str) -> String {
+        match prefix {
+            "" => format!("[c:{}]", self.short_hash(commit)),
+            prefix => format!("[{}/c:{}]", prefix, self.short_hash(commit)),
+        }
+    }
+
+    /// Return the URL to the first contribution of a new contributor: its pull request,
+    /// or else its commit.
+    pub fn contribution_url(&self, contributor: &NewContributor) -> String {
+        match contributor.pull_request {
+            Some(number) => self.pull_request_url(number),
+            None => self.commit_url(contributor.commit.hash()),
+        }
+    }
+
+    /// Return the URL to a pull request of the repository.
+    pub fn pull_request_url(&self, number: u64) -> String {
+        if let Some(template) = &self.url_templates.pull_request_url {
+            return self.expand_url(template, &[("number", &number.to_string())]);
+        }
+
+        match self.forge() {
+            Forge::Github => format!("{}/pull/{}", self.repo_url, number),
+            Forge::Gitlab => format!("{}/-/merge_requests/{}", self.repo_url, number),
+            Forge::Gitea => format!("{}/pulls/{}", self.repo_url, number),
+            Forge::Azure => format!("{}/pullrequest/{}", self.repo_url, number),
+        }
+    }
+
+    /// Return the reference to a pull request as the forge writes it, such as `#123`,
+    /// or `!123` for a merge request on GitLab.
+    pub fn pull_request_ref(&self, number: u64) -> String {
+        match self.forge() {
+            Forge::Gitlab => format!("!{}", number),
+            Forge::Github | Forge::Gitea | Forge::Azure => format!("#{}", number),
+        }
+    }
+
+    /// Return the URL to the comparison of two revisions of the repository.
+    pub fn compare_url(&self, from: &str, to: &str) -> String {
+        if let Some(template) = &self.url_templates.compare_url {
+            return self.expand_url(template, &[("from", from), ("to", to)]);
+        }
+
+        match self.forge() {
+            Forge::Github | Forge::Gitea => {
+                format!("{}/compare/{}...{}", self.repo_url, from, to)
+            }
+            Forge::Gitlab => format!("{}/-/compare/{}...{}", self.repo_url, from, to),
+            Forge::Azure => format!(
+                "{}/branchCompare?baseVersion=GT{}&targetVersion=GT{}",
+                self.repo_url, from, to
+            ),
+        }
+    }
+
+    /// Return the URL to the comparison of the previous release with this one, if both of
+    /// their versions are known.
+    pub fn full_changelog_url(&self) -> Option<String> {
+        let previous = self.previous_version.as_deref()?;
+        let version = self.version.as_deref()?;
+
+        Some(self.compare_url(previous, version))
+    }
+
+    /// Return the URL to a tag of the repository.
+    pub fn tag_url(&self, tag: &str) -> String {
+        if let Some(template) = &self.url_templates.tag_url {
+            return self.expand_url(template, &[("tag", tag)]);
+        }
+
+        match self.forge() {
+            Forge::Github | Forge::Gitea => format!("{}/releases/tag/{}", self.repo_url, tag),
+            Forge::Gitlab => format!("{}/-/tags/{}", self.repo_url, tag),
+            Forge::Azure => format!("{}?version=GT{}", self.repo_url, tag),
+        }
+    }
+
+    /// Return where the name of an author links to, or `None` if it should be rendered as plain text.
+    pub fn get_author_link(&self, author: &Author) -> Option<AuthorLink> {
+        let has_profiles = self.author_url.is_some() || self.forge() != Forge::Azure;
+
+        if author.is_username() && has_profiles {
+            return Some(AuthorLink::Profile(self.get_author_url(author)));
+        }
+
+        match self.author_fallback.unwrap_or_default() {
+            AuthorFallback::Text => None,
+            AuthorFallback::Mailto => author
+                .email()
+                .map(|email| AuthorLink::Mail(format!("mailto:{}", email))),
+            AuthorFallback::Link if has_profiles => {
+                Some(AuthorLink::Profile(self.get_author_url(author)))
+            }
+            AuthorFallback::Link => None,
+        }
+    }
+
+    /// Return all unique authors of the whole release, including those of its components,
+    /// in the order of their first appearance.
+    pub fn authors(&self) -> IndexSet<&Author> {
+        self.iter_all()
+            .flat_map(|Change(_, _, OneOrMore(authors), ..)| authors.iter())
             .collect()
     }
 
-    /// Return all commits of the whole release.
-    pub fn get_commits(&self) -> Vec<Commit> {
+    /// Return all unique authors of the whole release, sorted by their names alphabetically.
+    ///
+    /// See [`sort_authors`].
+    ///
+    /// [`sort_authors`]: fn.sort_authors.html
+    pub fn sorted_authors(&self) -> Vec<&Author> {
+        let mut authors = self.authors().into_iter().collect::<Vec<_>>();
+        sort_authors(&mut authors);
+        authors
+    }
+
+    /// Expand the abbreviated hashes of all commits of the whole release to full hashes,
+    /// by looking them up in a repository.
+    pub fn expand_hashes(&mut self, repo: &Repository) -> Result<(), Error> {
+        for Change(_, _, _, OneOrMore(commits), _) in self.iter_mut() {
+            for commit in commits {
+                *commit = Commit(repo.expand_hash(commit.hash())?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return an iterator of all commits of the whole release, excluding those of its components.
+    pub fn commits(&self) -> impl Iterator<Item = &Commit> + '_ {
         self.iter()
-            .flat_map(|Change(_, _, _, OneOrMore(commits))| commits.iter().cloned())
+            .flat_map(|Change(_, _, _, OneOrMore(commits), ..)| commits.iter())
+    }
+
+    /// Return the statistics of the whole release, including its components.
+    pub fn stats(&self) -> Stats {
+        let commits = self
+            .iter_all()
+            .flat_map(|Change(_, _, _, OneOrMore(commits), ..)| commits.iter())
+            .collect::<HashSet<&Commit>>();
+
+        Stats {
+            changes: self.iter_all().count(),
+            contributors: self.authors().len(),
+            commits: commits.len(),
+        }
+    }
+
+    /// Return all unique pull requests of the whole release, in order of appearance.
+    pub fn get_pull_requests(&self) -> Vec<PullRequest> {
+        let mut seen = HashSet::new();
+
+        self.iter()
+            .filter_map(Change::pull_request)
+            .filter(|pr| seen.insert(*pr))
             .collect()
     }
 }
 
+/// Sort authors by their names alphabetically, ignoring case.
+pub fn sort_authors(authors: &mut [&Author]) {
+    authors.sort_by_cached_key(|a| a.name().to_lowercase());
+}
+
+/// Escape the characters of `text` that Markdown would interpret, such as `*` and `[`.
+///
+/// Code spans enclosed in matching backticks are kept, as their contents are written verbatim;
+/// a backtick without a match is escaped.
+pub fn escape_markdown(text: &str) -> Cow<'_, str> {
+    fn escape_into(res: &mut String, text: &str) {
+        for c in text.chars() {
+            if matches!(c, '\\' | '[' | ']' | '*' | '_' | '<' | '`') {
+                res.push('\\');
+            }
+
+            res.push(c);
+        }
+    }
+
+    if !text.contains(&['\\', '[', ']', '*', '_', '<', '`'][..]) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut res = String::with_capacity(text.len() + 8);
+    let mut rest = text;
+
+    while let Some(start) = rest.find('`') {
+        let (before, span) = rest.split_at(start);
+        escape_into(&mut res, before);
+
+        let ticks = span.len() - span.trim_start_matches('`').len();
+        let fence = &span[..ticks];
+
+        match span[ticks..].find(fence) {
+            Some(end) => {
+                let end = ticks + end + ticks;
+                res.push_str(&span[..end]);
+                rest = &span[end..];
+            }
+            None => {
+                escape_into(&mut res, fence);
+                rest = &span[ticks..];
+            }
+        }
+    }
+
+    escape_into(&mut res, rest);
+
+    Cow::Owned(res)
+}
+
+/// Normalize the URL to a repository.
+///
+/// Trailing slashes and a `.git` suffix are stripped, and the scp-like syntax of Git,
+/// such as `git@github.com:owner/repo`, is converted to `https://github.com/owner/repo`,
+/// as are `ssh://`, `git+ssh://` and `git://` URLs, without their user and port.
+/// The user and password of http(s) URLs are stripped as well.
+///
+/// The SSH URLs of Azure DevOps, such as `git@ssh.dev.azure.com:v3/org/project/repo`,
+/// are converted to the URLs of their web interface, such as
+/// `https://dev.azure.com/org/project/_git/repo`.
+///
+/// # Errors
+/// An error is returned if the result is not an http(s) URL.
+pub fn normalize_url(url: &str) -> Result<String, Error> {
+    let invalid = || Error::InvalidUrl(url.to_string());
+
+    let trimmed = url.trim();
+
+    let ssh = ["ssh://", "git+ssh://", "ssh+git://", "git://"]
+        .iter()
+        .find_map(|scheme| trimmed.strip_prefix(scheme));
+
+    let normalized = if let Some(rest) = ssh {
+        let (authority, path) = rest.split_once('/').ok_or_else(invalid)?;
+        let host = authority.rsplit('@').next().unwrap_or(authority);
+        let host = host.split(':').next().unwrap_or(host);
+        format!("https://{}/{}", host, path)
+    } else if let Some((scheme, rest)) = trimmed.split_once("://") {
+        // Remove credentials, such as the organization in `https://org@dev.azure.com/...`.
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let host = authority.rsplit('@').next().unwrap_or(authority);
+        format!("{}://{}/{}", scheme, host, path)
+    } else {
+        // scp-like syntax: `[user@]host:path`.
+        match trimmed.split_once(':') {
+            Some((host, path)) if !host.contains('/') && !path.is_empty() => {
+                let host = host.rsplit('@').next().unwrap_or(host);
+                format!("https://{}/{}", host, path.trim_start_matches('/'))
+            }
+            _ => return Err(invalid()),
+        }
+    };
+
+    let normalized = normalized.trim_end_matches('/');
+    let normalized = normalized
+        .strip_suffix(".git")
+        .unwrap_or(normalized)
+        .trim_end_matches('/');
+
+    let rest = normalized
+        .strip_prefix("https://")
+        .or_else(|| normalized.strip_prefix("http://"))
+        .ok_or_else(invalid)?;
+
+    if rest.split('/').next().is_none_or(str::is_empty) {
+        return Err(invalid());
+    }
+
+    if let Some(url) = azure_web_url(rest) {
+        return Ok(url);
+    }
+
+    Ok(normalized.to_string())
+}
+
+/// Convert the host and path of the SSH URL of an Azure DevOps repository, such as
+/// `ssh.dev.azure.com/v3/org/project/repo`, to the URL of its web interface.
+fn azure_web_url(url: &str) -> Option<String> {
+    let (host, path) = url.split_once('/')?;
+    let path = path.strip_prefix("v3/")?;
+
+    let mut parts = path.split('/');
+    let (org, project, repo) = (parts.next()?, parts.next()?, parts.next()?);
+
+    if parts.next().is_some() {
+        return None;
+    }
+
+    match host {
+        "ssh.dev.azure.com" => Some(format!(
+            "https://dev.azure.com/{}/{}/_git/{}",
+            org, project, repo
+        )),
+        "vs-ssh.visualstudio.com" => Some(format!(
+            "https://{}.visualstudio.com/{}/_git/{}",
+            org, project, repo
+        )),
+        _ => None,
+    }
+}
+
+/// Return the scheme and host of a URL, such as `https://github.com` of `https://github.com/owner/repo`.
+///
+/// If the URL has no scheme, `https://github.com` is returned.
+fn host_url(url: &str) -> &str {
+    let start = match url.find("://") {
+        Some(start) => start + 3,
+        None => return "https://github.com",
+    };
+
+    match url[start..].find('/') {
+        Some(end) => &url[..start + end],
+        None => url,
+    }
+}
+
 fn write_separated<T, It>(source: &mut dyn fmt::Write, it: It, sep: &str) -> fmt::Result
 where
     It: IntoIterator<Item = T>,
@@ -298,7 +1448,26 @@ where
     Ok(())
 }
 
-fn write_list(source: &mut dyn fmt::Write, header: &str, changes: &[Change]) -> fmt::Result {
+fn write_author(source: &mut dyn fmt::Write, rel: &Release, author: &Author) -> fmt::Result {
+    match rel.get_author_link(author) {
+        Some(AuthorLink::Profile(_)) => write!(source, "{}", author),
+        Some(AuthorLink::Mail(url)) => write!(source, "[{}]({})", author.name(), url),
+        None => source.write_str(author.name()),
+    }
+}
+
+/// Write a list of changes under a header.
+///
+/// The labels of links to pull requests and commits are prefixed by `prefix`, such as
+/// `[core#123]` and `[core/c:1a2b3c4]`, to distinguish those of components.
+fn write_list(
+    source: &mut dyn fmt::Write,
+    header: &str,
+    rel: &Release,
+    part: &Release,
+    changes: &[Change],
+    prefix: &str,
+) -> fmt::Result {
     if changes.is_empty() {
         return Ok(());
     }
@@ -306,15 +1475,40 @@ fn write_list(source: &mut dyn fmt::Write, header: &str, changes: &[Change]) ->
     writeln!(source, "{}\n", header)?;
 
     for change in changes {
-        let Change(category, name, OneOrMore(authors), OneOrMore(commits)) = change;
+        let Change(category, name, OneOrMore(authors), OneOrMore(commits), _) = change;
+
+        if category.is_empty() {
+            return Err(fmt::Error);
+        }
+
+        let (name, pr) = PullRequest::split(name);
+
+        write!(
+            source,
+            "- [{}] {} (",
+            rel.markdown_text(category),
+            rel.markdown_text(name)
+        )?;
+
+        for (i, author) in authors.iter().enumerate() {
+            if i != 0 {
+                source.write_str(" ")?;
+            }
 
-        assert!(!category.is_empty(), "categores cannot be empty");
+            write_author(source, rel, author)?;
+        }
 
-        write!(source, "- [{}] {} (", category, name)?;
-        write_separated(source, authors, " ")?;
         write!(source, ") ")?;
 
-        write_separated(source, commits, " ")?;
+        write_separated(
+            source,
+            commits.iter().map(|c| rel.commit_label(c, prefix)),
+            " ",
+        )?;
+
+        if let Some(PullRequest(number)) = pr {
+            write!(source, " [{}{}]", prefix, part.pull_request_ref(number))?;
+        }
 
         writeln!(source)?;
     }
@@ -324,46 +1518,297 @@ fn write_list(source: &mut dyn fmt::Write, header: &str, changes: &[Change]) ->
     Ok(())
 }
 
+/// Adapts a writer implementing [`std::io::Write`], such as a file or standard output,
+/// to [`std::fmt::Write`], so that output is streamed to it rather than built in memory.
+///
+/// As [`fmt::Error`] carries no information, the I/O error of a failed write is retained,
+/// and returned by [`into_inner`].
+///
+/// [`std::io::Write`]: std::io::Write
+/// [`std::fmt::Write`]: std::fmt::Write
+/// [`fmt::Error`]: std::fmt::Error
+/// [`into_inner`]: #method.into_inner
+pub struct IoWriter<W> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> IoWriter<W> {
+    /// Create a new adapter of the `inner` writer.
+    pub fn new(inner: W) -> Self {
+        Self { inner, error: None }
+    }
+
+    /// Return the inner writer, or the error of a write to it that failed.
+    pub fn into_inner(self) -> io::Result<W> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.inner),
+        }
+    }
+}
+
+impl<W: io::Write> fmt::Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
 /// Generate the output message from a [`Release`] by writing to a source implementing
 /// [`std::fmt::Write`]
 ///
+/// See [`generate_msg_io`] to write to a file or standard output directly.
+///
+/// Fails if a change has an empty category, which [`Release::check_categories`] reports
+/// with the offending change.
+///
 /// [`Release`]: struct.Release.html
 /// [`std::fmt::Write`]: std::fmt::Write
+/// [`generate_msg_io`]: fn.generate_msg_io.html
+/// [`Release::check_categories`]: struct.Release.html#method.check_categories
 pub fn generate_msg(source: &mut dyn fmt::Write, rel: &Release) -> fmt::Result {
-    writeln!(source, "Thanks to the following for their contributions:\n")?;
+    if let Some(heading) = rel.heading() {
+        writeln!(source, "# {}\n", heading)?;
+    }
 
-    let mut authors = rel.get_authors();
-    // Sort authors by their names alphabetically.
-    authors.sort_by(|a, b| a.name().to_lowercase().cmp(&b.name().to_lowercase()));
+    if let Some(intro) = &rel.intro {
+        writeln!(source, "{}\n", intro.trim_end())?;
+    }
 
-    let commits = rel.get_commits();
+    if let Some(summary) = &rel.summary {
+        writeln!(source, "{}\n", summary.trim_end())?;
+    }
+
+    writeln!(source, "{}\n", rel.strings.contributors())?;
+
+    let authors = rel.sorted_authors();
 
     for author in &authors {
-        writeln!(source, "- {}", author)?;
+        write!(source, "- ")?;
+        write_author(source, rel, author)?;
+        writeln!(source)?;
     }
 
     writeln!(source)?;
 
-    write_list(source, "### Added", &rel.added)?;
-    write_list(source, "### Changed", &rel.changed)?;
-    write_list(source, "### Fixed", &rel.fixed)?;
-    write_list(source, "### Removed", &rel.removed)?;
+    let mut reviewers = rel.reviewers.iter().collect::<Vec<_>>();
+    sort_authors(&mut reviewers);
 
-    for author in authors {
-        writeln!(source, "{}: https://github.com/{}", author, author.name())?;
+    if !reviewers.is_empty() {
+        writeln!(source, "{}\n", rel.strings.reviewers())?;
+
+        for reviewer in &reviewers {
+            write!(source, "- ")?;
+            write_author(source, rel, reviewer)?;
+            writeln!(source)?;
+        }
+
+        writeln!(source)?;
+    }
+
+    write_sections(source, "###", rel, rel, "")?;
+
+    for component in &rel.components {
+        writeln!(source, "### {}\n", component.name)?;
+        write_sections(source, "####", rel, &component.release, &component.name)?;
+    }
+
+    if !rel.new_contributors.is_empty() {
+        writeln!(source, "## {}\n", rel.strings.new_contributors())?;
+
+        for contributor in &rel.new_contributors {
+            write!(source, "- ")?;
+            write_author(source, rel, &contributor.author)?;
+            writeln!(
+                source,
+                " {} {}",
+                rel.strings.first_contribution(),
+                rel.contribution_url(contributor)
+            )?;
+        }
+
+        writeln!(source)?;
+    }
+
+    if let Some(epilogue) = &rel.epilogue {
+        writeln!(source, "{}\n", epilogue.trim_end())?;
+    }
+
+    if let Some(url) = rel.full_changelog_url() {
+        writeln!(source, "**{}**: {}\n", rel.strings.full_changelog(), url)?;
+    }
+
+    // Reviewers and new contributors that are also authors only get one link.
+    let linked = authors
+        .into_iter()
+        .chain(reviewers)
+        .chain(rel.new_contributors.iter().map(|c| &c.author))
+        .collect::<IndexSet<_>>();
+
+    for author in linked {
+        if let Some(AuthorLink::Profile(url)) = rel.get_author_link(author) {
+            writeln!(source, "{}: {}", author, url)?;
+        }
     }
 
     writeln!(source)?;
 
-    for commit in commits {
+    write_commit_links(source, rel, rel, "")?;
+
+    for component in &rel.components {
+        write_commit_links(source, rel, &component.release, &component.name)?;
+    }
+
+    let parts = std::iter::once((rel, ""))
+        .chain(rel.components.iter().map(|c| (&c.release, c.name.as_str())));
+
+    let mut prs = parts
+        .flat_map(|(part, prefix)| {
+            part.get_pull_requests()
+                .into_iter()
+                .map(move |pr| (part, prefix, pr))
+        })
+        .peekable();
+
+    if prs.peek().is_some() {
+        writeln!(source)?;
+    }
+
+    for (part, prefix, PullRequest(number)) in prs {
+        writeln!(
+            source,
+            "[{}{}]: {}",
+            prefix,
+            part.pull_request_ref(number),
+            part.pull_request_url(number)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Generate the output message from a [`Release`] like [`generate_msg`], but by streaming it
+/// to a writer implementing [`std::io::Write`], such as a file or standard output, rather than
+/// building it in memory.
+///
+/// Small writes are frequent, so the writer should be buffered, such as by [`BufWriter`].
+/// It is flushed at the end.
+///
+/// [`Release`]: struct.Release.html
+/// [`generate_msg`]: fn.generate_msg.html
+/// [`std::io::Write`]: std::io::Write
+/// [`BufWriter`]: std::io::BufWriter
+pub fn generate_msg_io<W: io::Write>(writer: W, rel: &Release) -> io::Result<()> {
+    let mut writer = IoWriter::new(writer);
+    let res = generate_msg(&mut writer, rel);
+    let mut writer = writer.into_inner()?;
+
+    res.map_err(|_| io::Error::other("failed to format the message"))?;
+
+    writer.flush()
+}
+
+fn write_sections(
+    source: &mut dyn fmt::Write,
+    level: &str,
+    rel: &Release,
+    part: &Release,
+    prefix: &str,
+) -> fmt::Result {
+    for section in rel.sections_of(part) {
+        let header = format!("{} {}", level, section.heading);
+        write_list(source, &header, rel, part, section.changes, prefix)?;
+    }
+
+    Ok(())
+}
+
+/// Write the links to the commits of `part`, which is either the release `rel` or one of its
+/// components, whose labels are prefixed by `prefix`.
+///
+/// Commits that several changes share are only defined once, in order of first appearance.
+fn write_commit_links(
+    source: &mut dyn fmt::Write,
+    rel: &Release,
+    part: &Release,
+    prefix: &str,
+) -> fmt::Result {
+    let mut seen = HashSet::new();
+
+    for commit in part.commits() {
+        if !seen.insert(rel.short_hash(commit)) {
+            continue;
+        }
+
         writeln!(
             source,
-            "{}: {}/commit/{}",
-            commit,
-            rel.repo_url,
-            commit.hash()
+            "{}: {}",
+            rel.commit_label(commit, prefix),
+            part.commit_url(commit.hash())
         )?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(changes: &str) -> serde_json::Result<Release> {
+        serde_json::from_str(&format!(
+            r#"{{"repo_url": "https://github.com/a/b", "added": [{}]}}"#,
+            changes
+        ))
+    }
+
+    #[test]
+    fn commits() {
+        assert_eq!(Commit::new("2d3e585").unwrap().short(), "2d3e585");
+        assert_eq!(
+            Commit::new("2d3e585506d2").unwrap().abbreviate(9),
+            "2d3e58550"
+        );
+        assert!(Commit::new("2d3").is_err());
+        assert!(Commit::new("ééééé").is_err());
+        assert!(Commit::try_from("main".to_string()).is_err());
+    }
+
+    #[test]
+    fn one_or_more() {
+        let change = release(r#"["model", "Stickers", ["Erk-", "Alex"], "2d3e585"]"#).unwrap();
+        assert_eq!(change.added[0].2 .0.len(), 2);
+
+        assert!(release(r#"["model", "Stickers", [], "2d3e585"]"#).is_err());
+        assert!(release(r#"["model", "Stickers", "Erk-", []]"#).is_err());
+        assert!(release(r#"["model", "Stickers", "Erk-", "ééééé"]"#).is_err());
+    }
+
+    #[test]
+    fn empty_category() {
+        let rel = release(r#"["", "Stickers", "Erk-", "2d3e585"]"#).unwrap();
+        let mut msg = String::new();
+
+        assert!(rel.check_categories().is_err());
+        assert_eq!(generate_msg(&mut msg, &rel), Err(fmt::Error));
+    }
+
+    #[test]
+    fn message() {
+        let rel = release(r#"["model", "Stickers", "Erk-", "2d3e585506d2"]"#).unwrap();
+        let mut msg = String::new();
+
+        generate_msg(&mut msg, &rel).unwrap();
+
+        assert!(msg.contains("[model] Stickers"), "{}", msg);
+        assert!(msg.contains("[c:2d3e585]"), "{}", msg);
+        assert!(
+            msg.contains("https://github.com/a/b/commit/2d3e585506d2"),
+            "{}",
+            msg
+        );
+    }
+}
@@ -0,0 +1,696 @@
+use crate::config::{Config, Gitea, Profile, Style};
+use crate::git::Repository;
+use crate::locale::{Language, Strings};
+use crate::placeholders::Placeholders;
+use crate::release::{
+    self, generate_msg, AuthorFallback, Forge, IoWriter, Release, Section, UrlTemplates,
+};
+use crate::resolved::ResolvedRelease;
+use crate::store::Store;
+use crate::template::Template;
+use crate::{csv, discord, html, keep_a_changelog, slack, style, text, Format, Result};
+
+use clap::ArgEnum;
+use serde::Serialize;
+use serde_json::to_string_pretty;
+use tracing::{debug, info};
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::channel;
+
+/// Whether a path is `-`, which stands for standard input.
+pub fn is_stdin(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Write the output to a file atomically, by streaming it from `write` to a temporary file next
+/// to it and renaming it over the file, so that an interrupted write never leaves a truncated file.
+pub fn write_file<F>(path: &Path, write: F) -> Result<()>
+where
+    F: FnOnce(&mut File) -> Result<()>,
+{
+    let context = |err: &dyn std::error::Error| format!("{}: {}", path.display(), err);
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| format!("{} is not a path to a file", path.display()))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp = path.with_file_name(temp_name);
+
+    let mut file = File::create(&temp).map_err(|err| context(&err))?;
+
+    if let Err(err) = write(&mut file) {
+        drop(file);
+        let _ = std::fs::remove_file(&temp);
+        return Err(context(&*err).into());
+    }
+
+    drop(file);
+
+    if let Err(err) = std::fs::rename(&temp, path) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(context(&err).into());
+    }
+
+    Ok(())
+}
+
+/// Serialize a value as pretty JSON, followed by a newline, into a writer, such as of a file
+/// or standard output.
+///
+/// The JSON is streamed to the writer as it is serialized, rather than built in memory,
+/// which matters for releases of huge histories.
+pub fn write_json<W, T>(out: W, value: &T) -> Result<()>
+where
+    W: io::Write,
+    T: Serialize,
+{
+    let mut out = BufWriter::new(out);
+    serde_json::to_writer_pretty(&mut out, value)?;
+    writeln!(out)?;
+    out.flush()?;
+
+    Ok(())
+}
+
+/// The format of input files.
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl InputFormat {
+    /// Detect the format of a file by its extension.
+    pub fn detect(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(InputFormat::Json),
+            "toml" => Some(InputFormat::Toml),
+            "yaml" | "yml" => Some(InputFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Read a release from a file, or from standard input if the path is `-`, in the given format,
+/// or else in the format detected by its extension, which defaults to JSON.
+fn read_release(path: &Path, format: Option<InputFormat>) -> Result<Release> {
+    let context = |err: Box<dyn std::error::Error>| -> Box<dyn std::error::Error> {
+        if is_stdin(path) {
+            format!("standard input: {}", err).into()
+        } else {
+            format!("{}: {}", path.display(), err).into()
+        }
+    };
+
+    let reader: Box<dyn std::io::Read> = if is_stdin(path) {
+        Box::new(std::io::stdin())
+    } else {
+        Box::new(File::open(path).map_err(|err| context(err.into()))?)
+    };
+
+    let mut reader = std::io::BufReader::new(reader);
+
+    let format = format
+        .or_else(|| InputFormat::detect(path))
+        .unwrap_or(InputFormat::Json);
+
+    let mut release: Release = match format {
+        InputFormat::Json => {
+            serde_json::from_reader(&mut reader).map_err(|err| context(err.into()))?
+        }
+        InputFormat::Toml => {
+            let mut text = String::new();
+            reader
+                .read_to_string(&mut text)
+                .map_err(|err| context(err.into()))?;
+            toml::from_str(&text).map_err(|err| context(err.into()))?
+        }
+        InputFormat::Yaml => {
+            serde_yaml::from_reader(&mut reader).map_err(|err| context(err.into()))?
+        }
+    };
+    release
+        .normalize_urls()
+        .map_err(|err| context(err.into()))?;
+
+    Ok(release)
+}
+
+/// Read the releases of the files and merge them into one, reading standard input if there
+/// are no files.
+///
+/// The releases must be of the same repository.
+pub fn read_releases(paths: &[PathBuf], format: Option<InputFormat>) -> Result<Release> {
+    if paths.is_empty() {
+        return read_release(Path::new("-"), format);
+    }
+
+    if paths.iter().filter(|p| is_stdin(p)).count() > 1 {
+        return Err("standard input (`-`) may only be specified once".into());
+    }
+
+    let mut paths = paths.iter();
+    // There is at least one path, as checked above.
+    let mut release = read_release(paths.next().unwrap(), format)?;
+
+    for path in paths {
+        let other = read_release(path, format)?;
+
+        if other.repo_url != release.repo_url {
+            return Err(format!(
+                "{}: repository URL `{}` does not match `{}`",
+                path.display(),
+                other.repo_url,
+                release.repo_url
+            )
+            .into());
+        }
+
+        release.merge(other);
+    }
+
+    Ok(release)
+}
+
+/// The releases of the input, after they are prepared for rendering.
+pub enum Input {
+    /// A single release, merged from all input files.
+    Release(Box<Release>),
+    /// Every release of a directory of stored releases, newest first.
+    Store(Vec<(String, Release)>),
+}
+
+/// Renders releases into the output.
+#[derive(Default)]
+pub struct Renderer {
+    /// The values of the placeholders substituted in the texts of releases.
+    pub placeholders: Placeholders,
+    /// The template of the summary of releases without one.
+    /// If empty, the default summary is used.
+    pub summary: Option<String>,
+    /// The templates of the URLs to commits, pull requests, comparisons, and tags,
+    /// overriding the releases' own.
+    pub url_templates: UrlTemplates,
+    /// The template of the URL to the profiles of authors, overriding the releases' own.
+    pub author_url: Option<String>,
+    /// How to render authors without usernames, overriding the releases' own.
+    pub author_fallback: Option<AuthorFallback>,
+    /// The host of the repositories, overriding the releases' own.
+    pub forge: Option<Forge>,
+    /// The self-hosted Gitea instances, whose repositories are linked to as such.
+    pub gitea: Gitea,
+    /// The style that titles are checked against, if they are linted.
+    pub style: Option<Style>,
+    /// The maximum length of titles, if they are truncated.
+    pub truncate_titles: Option<usize>,
+    /// The order of sections, overriding the releases' own if not empty.
+    pub section_order: Vec<String>,
+    /// Headings of sections, overriding the releases' own.
+    pub headings: BTreeMap<String, String>,
+    /// The length that commit hashes are abbreviated to, overriding the releases' own.
+    pub hash_length: Option<usize>,
+    /// Whether names of changes are written without escaping, in addition to releases that
+    /// request it.
+    pub raw_names: bool,
+    /// The language of the headings and texts absent from the releases.
+    pub language: Option<Language>,
+    /// The texts of the output, overriding the releases' own.
+    pub strings: Strings,
+    /// The repository in which abbreviated commit hashes are expanded.
+    pub repository: Option<Repository>,
+    /// The template of the output, taking precedence over the format.
+    pub template: Option<Template>,
+    /// The format of the input files, instead of the format detected by their extensions.
+    pub input_format: Option<InputFormat>,
+}
+
+impl Renderer {
+    /// Create a renderer with the options of a profile of the configuration, which checks
+    /// titles against the style of the configuration if the profile lints them.
+    pub fn from_profile(profile: &Profile, config: &Config) -> Result<Self> {
+        let summary = match &profile.summary_template {
+            Some(template) => Some(template.clone()),
+            None if profile.summary => Some(String::new()),
+            None => None,
+        };
+
+        Ok(Renderer {
+            placeholders: Placeholders::new(profile.vars.clone()),
+            summary,
+            url_templates: profile.url_templates(),
+            author_url: profile.author_url.clone(),
+            author_fallback: profile.author_fallback,
+            forge: profile.forge,
+            gitea: config.gitea.clone(),
+            style: if profile.lint {
+                Some(config.style.clone())
+            } else {
+                None
+            },
+            truncate_titles: profile.truncate_titles,
+            section_order: profile.section_order.clone(),
+            headings: profile.headings.clone(),
+            hash_length: profile.hash_length,
+            raw_names: profile.raw_names,
+            language: profile.lang,
+            strings: profile.strings.clone(),
+            template: profile.template.as_ref().map(Template::load).transpose()?,
+            ..Renderer::default()
+        })
+    }
+
+    /// Print warnings about titles of a release violating the style, if they are linted.
+    pub fn lint(&self, version: Option<&str>, release: &Release) {
+        let style = match &self.style {
+            Some(style) => style,
+            None => return,
+        };
+
+        for (section, title, issues) in style::lint(style, release) {
+            for issue in issues {
+                match version {
+                    Some(version) => {
+                        eprintln!("warning: {}: {}: \"{}\" {}", version, section, title, issue)
+                    }
+                    None => eprintln!("warning: {}: \"{}\" {}", section, title, issue),
+                }
+            }
+        }
+    }
+
+    /// Apply the overrides and substitute the placeholders of a release.
+    pub fn prepare(&self, mut release: Release) -> Result<Release> {
+        let stats = release.stats();
+
+        release.url_templates = self.url_templates.clone().or(release.url_templates);
+
+        for component in &mut release.components {
+            let templates = std::mem::take(&mut component.release.url_templates);
+            component.release.url_templates = self.url_templates.clone().or(templates);
+        }
+
+        if let Some(author_url) = &self.author_url {
+            release.author_url = Some(author_url.clone());
+        }
+
+        if let Some(author_fallback) = self.author_fallback {
+            release.author_fallback = Some(author_fallback);
+        }
+
+        if let Some(forge) = self.forge {
+            release.forge = Some(forge);
+
+            for component in &mut release.components {
+                component.release.forge = Some(forge);
+            }
+        }
+
+        self.gitea.detect(&mut release);
+
+        if !self.section_order.is_empty() {
+            release.section_order = self.section_order.clone();
+        }
+
+        release.headings.extend(self.headings.clone());
+
+        if let Some(len) = self.hash_length {
+            release.hash_length = Some(len);
+        }
+
+        if self.raw_names {
+            release.raw_names = true;
+
+            for component in &mut release.components {
+                component.release.raw_names = true;
+            }
+        }
+
+        if release.hash_length() < release::MIN_HASH_LENGTH {
+            return Err(format!(
+                "hash length {} is shorter than {} characters",
+                release.hash_length(),
+                release::MIN_HASH_LENGTH
+            )
+            .into());
+        }
+
+        release.check_categories()?;
+
+        let strings = std::mem::take(&mut release.strings);
+        release.strings = self.strings.clone().or(strings);
+
+        if let Some(lang) = self.language {
+            release.strings = std::mem::take(&mut release.strings).or(lang.strings());
+
+            for section in Section::ALL {
+                release
+                    .headings
+                    .entry(section.name().to_string())
+                    .or_insert_with(|| lang.heading(section).to_string());
+            }
+        }
+
+        if let Some(repo) = &self.repository {
+            release.expand_hashes(repo)?;
+        }
+
+        if let Some(max) = self.truncate_titles {
+            style::truncate_titles(&mut release, max);
+        }
+
+        let mut placeholders = self.placeholders.clone();
+        placeholders.set("changes", stats.changes.to_string());
+        placeholders.set("contributors", stats.contributors.to_string());
+        placeholders.set("commits", stats.commits.to_string());
+
+        if release.summary.is_none() {
+            release.summary = match &self.summary {
+                Some(template) if template.is_empty() => Some(stats.to_string()),
+                summary => summary.clone(),
+            };
+        }
+
+        let texts = release
+            .intro
+            .iter_mut()
+            .chain(release.summary.iter_mut())
+            .chain(release.epilogue.iter_mut());
+
+        for text in texts {
+            *text = placeholders.substitute(text)?;
+        }
+
+        Ok(release)
+    }
+
+    /// Write a release, of a version if it is stored, into `res` in a format.
+    pub fn write_release(
+        &self,
+        res: &mut dyn fmt::Write,
+        format: Format,
+        version: Option<&str>,
+        release: &Release,
+    ) -> Result<()> {
+        match format {
+            Format::Markdown => generate_msg(res, release)?,
+            Format::Html => html::generate_html(res, release)?,
+            Format::Text => text::generate_text(res, release)?,
+            Format::Discord => discord::generate_discord(res, version, release)?,
+            Format::Slack => slack::generate_slack(res, release)?,
+            Format::Csv | Format::Tsv => {
+                csv::generate_csv(res, format.separator().unwrap(), version, release)?
+            }
+            Format::JsonResolved => writeln!(
+                res,
+                "{}",
+                to_string_pretty(&ResolvedRelease::new(version, release))?
+            )?,
+            Format::KeepAChangelog => {
+                let name = version.or(release.version.as_deref());
+                let date = match &release.date {
+                    Some(date) => Some(date.clone()),
+                    None => name.map(|v| self.release_date(v)).transpose()?.flatten(),
+                };
+
+                let version = keep_a_changelog::Version {
+                    name,
+                    date,
+                    release,
+                };
+
+                keep_a_changelog::generate_keep_a_changelog(res, &[version])?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return the date of the tag of a version, if there is a repository and the tag exists.
+    fn release_date(&self, version: &str) -> Result<Option<String>> {
+        let repo = match &self.repository {
+            Some(repo) => repo,
+            None => return Ok(None),
+        };
+
+        let date = repo
+            .tags(version)?
+            .into_iter()
+            .find(|tag| tag.name == version)
+            .map(|tag| tag.time.format("%Y-%m-%d").to_string());
+
+        Ok(date)
+    }
+
+    fn write_version(
+        &self,
+        res: &mut dyn fmt::Write,
+        format: Format,
+        version: &str,
+    ) -> fmt::Result {
+        match format {
+            Format::Markdown => write!(res, "## {}\n\n", version),
+            Format::Html => writeln!(res, "<h2>{}</h2>", version),
+            Format::Slack => write!(res, "*{}*\n\n", version),
+            Format::Text => write!(
+                res,
+                "{}\n{}\n\n",
+                version,
+                "=".repeat(version.chars().count())
+            ),
+            // Tabular and JSON formats carry the version in every row or release,
+            // Keep a Changelog in the heading of the release, and Discord in its messages.
+            Format::Csv
+            | Format::Tsv
+            | Format::JsonResolved
+            | Format::KeepAChangelog
+            | Format::Discord => Ok(()),
+        }
+    }
+
+    /// Read, lint, and prepare the releases of the input files, or of a directory
+    /// of stored releases.
+    pub fn load(&self, paths: &[PathBuf]) -> Result<Input> {
+        if let [dir] = paths {
+            if dir.is_dir() {
+                let store = Store::new(dir);
+
+                let releases = store
+                    .versions()?
+                    .into_iter()
+                    .map(|version| {
+                        let release = store.load(&version)?;
+                        self.lint(Some(&version), &release);
+                        Ok((version, self.prepare(release)?))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                return Ok(Input::Store(releases));
+            }
+        }
+
+        let release = read_releases(paths, self.input_format)?;
+        self.lint(None, &release);
+
+        Ok(Input::Release(Box::new(self.prepare(release)?)))
+    }
+
+    /// Render the input into a string.
+    pub fn render(&self, format: Format, input: &Input) -> Result<String> {
+        let mut res = String::new();
+        self.render_to(&mut res, format, input)?;
+
+        Ok(res)
+    }
+
+    /// Render the input into `res`, such as a writer of a file or standard output.
+    fn render_to(&self, res: &mut dyn fmt::Write, format: Format, input: &Input) -> Result<()> {
+        debug!("rendering the output as {:?}", format);
+
+        if let Some(template) = &self.template {
+            let output = match input {
+                Input::Release(release) => template.render(&ResolvedRelease::new(None, release))?,
+                Input::Store(releases) => releases
+                    .iter()
+                    .map(|(version, release)| {
+                        template.render(&ResolvedRelease::new(Some(version), release))
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .join("\n"),
+            };
+
+            res.write_str(&output)?;
+
+            return Ok(());
+        }
+
+        let releases = match input {
+            Input::Release(release) => {
+                if let Some(sep) = format.separator() {
+                    csv::write_header(res, sep, false)?;
+                }
+
+                self.write_release(res, format, None, release)?;
+
+                return Ok(());
+            }
+            Input::Store(releases) => releases,
+        };
+
+        if format == Format::JsonResolved {
+            let releases = releases
+                .iter()
+                .map(|(version, release)| ResolvedRelease::new(Some(version), release))
+                .collect::<Vec<_>>();
+
+            writeln!(res, "{}", to_string_pretty(&releases)?)?;
+
+            return Ok(());
+        }
+
+        if format == Format::KeepAChangelog {
+            let versions = releases
+                .iter()
+                .map(|(version, release)| {
+                    Ok(keep_a_changelog::Version {
+                        name: Some(version),
+                        date: self.release_date(version)?,
+                        release,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            keep_a_changelog::generate_keep_a_changelog(res, &versions)?;
+
+            return Ok(());
+        }
+
+        if let Some(sep) = format.separator() {
+            csv::write_header(res, sep, true)?;
+        }
+
+        for (i, (version, release)) in releases.iter().enumerate() {
+            if i != 0 && format.separator().is_none() {
+                writeln!(res)?;
+            }
+
+            // Releases with their own version are headed by it instead.
+            if release.version.is_none() {
+                self.write_version(res, format, version)?;
+            }
+
+            self.write_release(res, format, Some(version), release)?;
+        }
+
+        Ok(())
+    }
+
+    /// Render the input into a writer, such as of a file or standard output, streaming
+    /// the output rather than building it in memory.
+    pub fn render_io<W: io::Write>(&self, out: W, format: Format, input: &Input) -> Result<W> {
+        let mut out = IoWriter::new(BufWriter::new(out));
+        let res = self.render_to(&mut out, format, input);
+
+        // The error of a failed write takes precedence over the `fmt::Error` it caused.
+        let out = out.into_inner()?;
+        res?;
+
+        Ok(out.into_inner().map_err(|err| err.into_error())?)
+    }
+
+    /// Render the input to standard output, followed by an empty line.
+    pub fn print(&self, format: Format, input: &Input) -> Result<()> {
+        let mut stdout = self.render_io(io::stdout().lock(), format, input)?;
+        writeln!(stdout)?;
+
+        Ok(())
+    }
+
+    /// Render the input in every format of the targets, and write the output
+    /// to the respective paths.
+    pub fn write_targets(&self, paths: &[PathBuf], targets: &[(Format, PathBuf)]) -> Result<()> {
+        let input = self.load(paths)?;
+
+        for (format, path) in targets {
+            info!("writing {}", path.display());
+            write_file(path, |file| {
+                self.render_io(file, *format, &input)?;
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Render the input files into the targets, and render them again whenever the files change,
+/// until interrupted.
+pub fn watch(paths: &[PathBuf], targets: &[(Format, PathBuf)], renderer: &Renderer) -> Result<()> {
+    use notify::{RecursiveMode, Watcher};
+
+    if paths.iter().any(|p| is_stdin(p)) {
+        return Err("standard input (`-`) cannot be watched".into());
+    }
+
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+
+    let paths = paths
+        .iter()
+        .map(|p| p.canonicalize())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Editors commonly save files by writing to a temporary file and renaming it over
+    // the original, which removes the file being watched. Watch the parent directories
+    // instead, and filter for events related to the input files.
+    let mut dirs = paths
+        .iter()
+        .map(|p| p.parent().unwrap_or_else(|| Path::new(".")))
+        .collect::<Vec<_>>();
+    dirs.sort();
+    dirs.dedup();
+
+    for dir in dirs {
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    }
+
+    rerender(&paths, targets, renderer);
+
+    for event in &rx {
+        let event = event?;
+
+        if event.kind.is_access() || !event.paths.iter().any(|p| paths.contains(p)) {
+            continue;
+        }
+
+        // Coalesce the burst of events produced by a single save.
+        while rx
+            .recv_timeout(std::time::Duration::from_millis(50))
+            .is_ok()
+        {}
+
+        rerender(&paths, targets, renderer);
+    }
+
+    Ok(())
+}
+
+fn rerender(paths: &[PathBuf], targets: &[(Format, PathBuf)], renderer: &Renderer) {
+    // Errors are reported, but not fatal, so that a typo in the input
+    // does not end the session.
+    match renderer.write_targets(paths, targets) {
+        Ok(()) => {
+            for (_, path) in targets {
+                eprintln!("Rendered to {}", path.display());
+            }
+        }
+        Err(err) => eprintln!("Failed to render: {}", err),
+    }
+}
@@ -0,0 +1,213 @@
+use crate::git::Repository;
+use crate::release::Author;
+use crate::store::{self, Store};
+use crate::Result;
+
+use indexmap::IndexSet;
+use serde::Serialize;
+
+use std::collections::HashSet;
+use std::fmt;
+
+/// Statistics of one release, as defined by its tag.
+#[derive(Serialize, Debug, Clone)]
+pub struct ReleaseReport {
+    /// The name of the release's tag.
+    pub version: String,
+    /// The date of the release, as `YYYY-MM-DD`.
+    pub date: String,
+    /// The number of days since the previous release, if there is one.
+    pub days_since_previous: Option<i64>,
+    /// The number of commits since the previous release.
+    pub commits: usize,
+    /// The number of changes of the release.
+    ///
+    /// If the release is not stored, this is the number of commits.
+    pub changes: usize,
+    /// Whether the release is stored.
+    pub stored: bool,
+    /// The number of unique contributors of the release.
+    pub contributors: usize,
+    /// The number of contributors that did not contribute to any previous release.
+    pub new_contributors: usize,
+    /// The number of unique contributors of this and all previous releases.
+    pub total_contributors: usize,
+}
+
+/// Statistics of the cadence of releases.
+#[derive(Serialize, Debug, Clone)]
+pub struct Report {
+    /// The releases, oldest first.
+    pub releases: Vec<ReleaseReport>,
+}
+
+impl Report {
+    /// Analyze the releases of a repository, as defined by the tags matching the glob `pattern`.
+    ///
+    /// Stored releases are used to count changes and contributors. For releases that are not stored,
+    /// the commits between their tag and the previous release's tag are used instead.
+    pub fn new(repo: &Repository, store: &Store, pattern: &str) -> Result<Self> {
+        let mut tags = repo.tags(pattern)?;
+        // Oldest first.
+        tags.sort_by(|a, b| store::cmp_versions(&b.name, &a.name));
+
+        let mut seen = HashSet::new();
+        let mut releases = Vec::with_capacity(tags.len());
+
+        for (i, tag) in tags.iter().enumerate() {
+            let previous = i.checked_sub(1).map(|i| &tags[i]);
+            let previous_hash = previous.map(|tag| tag.hash.as_str());
+
+            let commits = repo.count_commits(previous_hash, &tag.hash)?;
+
+            let (changes, authors) = if store.contains(&tag.name) {
+                let release = store.load(&tag.name)?;
+                (
+                    release.stats().changes,
+                    release
+                        .authors()
+                        .into_iter()
+                        .cloned()
+                        .collect::<IndexSet<_>>(),
+                )
+            } else {
+                let authors = repo
+                    .range(previous_hash, &tag.hash)?
+                    .map(|commit| {
+                        commit.map(|c| Author::from_identity(&c.author.name, &c.author.email))
+                    })
+                    .collect::<std::result::Result<IndexSet<_>, _>>()?;
+
+                (commits, authors)
+            };
+
+            let contributors = authors.len();
+            let new_contributors = authors
+                .into_iter()
+                .filter(|author| seen.insert(author.name().to_lowercase()))
+                .count();
+
+            releases.push(ReleaseReport {
+                version: tag.name.clone(),
+                date: tag.time.format("%Y-%m-%d").to_string(),
+                days_since_previous: previous.map(|p| (tag.time - p.time).num_days()),
+                commits,
+                changes,
+                stored: store.contains(&tag.name),
+                contributors,
+                new_contributors,
+                total_contributors: seen.len(),
+            });
+        }
+
+        Ok(Self { releases })
+    }
+
+    fn average<F>(&self, f: F) -> Option<f64>
+    where
+        F: Fn(&ReleaseReport) -> Option<f64>,
+    {
+        let values = self.releases.iter().filter_map(f).collect::<Vec<_>>();
+
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.iter().sum::<f64>() / values.len() as f64)
+        }
+    }
+
+    /// Write the report as a table, followed by averages.
+    pub fn write_table(&self, source: &mut dyn fmt::Write) -> fmt::Result {
+        let width = self
+            .releases
+            .iter()
+            .map(|r| r.version.len())
+            .chain(std::iter::once("version".len()))
+            .max()
+            .unwrap_or(0);
+
+        writeln!(
+            source,
+            "{:width$}  {:10}  {:>5}  {:>7}  {:>7}  {:>12}  {:>3}  {:>5}",
+            "version",
+            "date",
+            "days",
+            "commits",
+            "changes",
+            "contributors",
+            "new",
+            "total",
+            width = width
+        )?;
+
+        for r in &self.releases {
+            let days = r
+                .days_since_previous
+                .map_or_else(|| "-".to_string(), |days| days.to_string());
+
+            writeln!(
+                source,
+                "{:width$}  {:10}  {:>5}  {:>7}  {:>7}  {:>12}  {:>3}  {:>5}",
+                r.version,
+                r.date,
+                days,
+                r.commits,
+                r.changes,
+                r.contributors,
+                r.new_contributors,
+                r.total_contributors,
+                width = width
+            )?;
+        }
+
+        if let Some(days) = self.average(|r| r.days_since_previous.map(|d| d as f64)) {
+            writeln!(source, "\nAverage days between releases: {:.1}", days)?;
+        }
+
+        if let Some(changes) = self.average(|r| Some(r.changes as f64)) {
+            writeln!(source, "Average changes per release: {:.1}", changes)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the report as comma-separated values, with a header.
+    pub fn write_csv(&self, source: &mut dyn fmt::Write) -> fmt::Result {
+        crate::csv::write_record(
+            source,
+            ',',
+            [
+                "version",
+                "date",
+                "days_since_previous",
+                "commits",
+                "changes",
+                "stored",
+                "contributors",
+                "new_contributors",
+                "total_contributors",
+            ],
+        )?;
+
+        for r in &self.releases {
+            crate::csv::write_record(
+                source,
+                ',',
+                [
+                    r.version.clone(),
+                    r.date.clone(),
+                    r.days_since_previous
+                        .map_or_else(String::new, |days| days.to_string()),
+                    r.commits.to_string(),
+                    r.changes.to_string(),
+                    r.stored.to_string(),
+                    r.contributors.to_string(),
+                    r.new_contributors.to_string(),
+                    r.total_contributors.to_string(),
+                ],
+            )?;
+        }
+
+        Ok(())
+    }
+}
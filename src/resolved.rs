@@ -0,0 +1,248 @@
+use crate::release::{
+    self, Author, AuthorLink, Change, Commit, OneOrMore, PullRequest, Release, Stats,
+};
+
+use serde::Serialize;
+
+use std::collections::HashSet;
+
+/// A [`Release`] after all processing, with its authors resolved and its links expanded
+/// into absolute URLs.
+///
+/// [`Release`]: ../release/struct.Release.html
+#[derive(Serialize, Debug, Clone)]
+pub struct ResolvedRelease {
+    /// The version of the release, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The date of the release, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The URL to the comparison with the previous release, if both versions are known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compare_url: Option<String>,
+    pub repo_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intro: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epilogue: Option<String>,
+    pub stats: Stats,
+    /// The unique authors of the release, sorted by their names.
+    pub contributors: Vec<ResolvedAuthor>,
+    /// The reviewers of the release, sorted by their names.
+    pub reviewers: Vec<ResolvedAuthor>,
+    /// The authors whose first contribution to the repository is part of the release.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub new_contributors: Vec<ResolvedNewContributor>,
+    /// The sections of the release that have changes, in the order they are rendered.
+    pub sections: Vec<ResolvedSection>,
+    /// The unique commits of the changes, in order of their first appearance.
+    pub commits: Vec<ResolvedCommit>,
+    /// The changes of other repositories aggregated into the release.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<ResolvedComponent>,
+}
+
+/// The changes of another repository aggregated into a release.
+#[derive(Serialize, Debug, Clone)]
+pub struct ResolvedComponent {
+    pub name: String,
+    pub repo_url: String,
+    pub sections: Vec<ResolvedSection>,
+    /// The unique commits of the changes, in order of their first appearance.
+    pub commits: Vec<ResolvedCommit>,
+}
+
+/// An author, with where their name links to.
+#[derive(Serialize, Debug, Clone)]
+pub struct ResolvedAuthor {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Whether the name is a username of the host of the repository.
+    pub username: bool,
+    /// The URL to the profile of the author, or a `mailto:` link.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// An author whose first contribution is part of the release, with the URL to the contribution.
+#[derive(Serialize, Debug, Clone)]
+pub struct ResolvedNewContributor {
+    pub author: ResolvedAuthor,
+    /// The URL to the pull request of the first contribution, or else to its commit.
+    pub url: String,
+}
+
+/// The changes of a section.
+#[derive(Serialize, Debug, Clone)]
+pub struct ResolvedSection {
+    /// The name of the section, as it appears in the input.
+    pub section: String,
+    /// The heading of the section, as it is rendered.
+    pub heading: String,
+    pub changes: Vec<ResolvedChange>,
+}
+
+/// A change, with its pull request separated from its title.
+#[derive(Serialize, Debug, Clone)]
+pub struct ResolvedChange {
+    pub category: String,
+    pub title: String,
+    /// The full text of the change, if the title is shortened.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub authors: Vec<ResolvedAuthor>,
+    pub commits: Vec<ResolvedCommit>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pull_request: Option<ResolvedPullRequest>,
+}
+
+/// A commit, with its URL.
+#[derive(Serialize, Debug, Clone)]
+pub struct ResolvedCommit {
+    pub hash: String,
+    /// The hash, abbreviated as it is rendered.
+    pub short: String,
+    pub url: String,
+}
+
+/// A pull request, with its URL.
+#[derive(Serialize, Debug, Clone)]
+pub struct ResolvedPullRequest {
+    pub number: u64,
+    /// The reference to the pull request, such as `#123`, or `!123` on GitLab.
+    pub reference: String,
+    pub url: String,
+}
+
+impl ResolvedAuthor {
+    fn new(rel: &Release, author: &Author) -> Self {
+        let url = rel.get_author_link(author).map(|link| match link {
+            AuthorLink::Profile(url) | AuthorLink::Mail(url) => url,
+        });
+
+        Self {
+            name: author.name().to_string(),
+            email: author.email().map(str::to_string),
+            username: author.is_username(),
+            url,
+        }
+    }
+
+    fn all(rel: &Release, mut authors: Vec<&Author>) -> Vec<Self> {
+        release::sort_authors(&mut authors);
+        authors.into_iter().map(|a| Self::new(rel, a)).collect()
+    }
+}
+
+impl ResolvedCommit {
+    /// Resolve a commit of `part`, which is either the release `rel` or one of its components.
+    fn new(rel: &Release, part: &Release, commit: &Commit) -> Self {
+        Self {
+            hash: commit.hash().to_string(),
+            short: rel.short_hash(commit).to_string(),
+            url: part.commit_url(commit.hash()),
+        }
+    }
+
+    /// Resolve the unique commits of `part`, which is either the release `rel` or one of its
+    /// components.
+    fn all(rel: &Release, part: &Release) -> Vec<Self> {
+        let mut seen = HashSet::new();
+
+        part.commits()
+            .filter(|commit| seen.insert(commit.hash()))
+            .map(|commit| Self::new(rel, part, commit))
+            .collect()
+    }
+}
+
+impl ResolvedSection {
+    /// Resolve the sections of `part`, which is either the release `rel` or one of its components.
+    fn all(rel: &Release, part: &Release) -> Vec<Self> {
+        rel.sections_of(part)
+            .into_iter()
+            .filter(|section| !section.changes.is_empty())
+            .map(|section| Self {
+                section: section.name.to_string(),
+                heading: section.heading.into_owned(),
+                changes: section
+                    .changes
+                    .iter()
+                    .map(|change| ResolvedChange::new(rel, part, change))
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+impl ResolvedChange {
+    fn new(rel: &Release, part: &Release, change: &Change) -> Self {
+        let Change(category, name, OneOrMore(authors), OneOrMore(commits), description) = change;
+
+        let (title, pr) = PullRequest::split(name);
+
+        Self {
+            category: category.clone(),
+            title: title.to_string(),
+            description: description.clone(),
+            authors: authors
+                .iter()
+                .map(|a| ResolvedAuthor::new(rel, a))
+                .collect(),
+            commits: commits
+                .iter()
+                .map(|commit| ResolvedCommit::new(rel, part, commit))
+                .collect(),
+            pull_request: pr.map(|PullRequest(number)| ResolvedPullRequest {
+                number,
+                reference: part.pull_request_ref(number),
+                url: part.pull_request_url(number),
+            }),
+        }
+    }
+}
+
+impl ResolvedRelease {
+    /// Resolve a release, optionally of a known version.
+    pub fn new(version: Option<&str>, rel: &Release) -> Self {
+        Self {
+            version: version.map(str::to_string).or_else(|| rel.version.clone()),
+            date: rel.date.clone(),
+            title: rel.title.clone(),
+            compare_url: rel.full_changelog_url(),
+            repo_url: rel.repo_url.clone(),
+            intro: rel.intro.clone(),
+            summary: rel.summary.clone(),
+            epilogue: rel.epilogue.clone(),
+            stats: rel.stats(),
+            contributors: ResolvedAuthor::all(rel, rel.authors().into_iter().collect()),
+            reviewers: ResolvedAuthor::all(rel, rel.reviewers.iter().collect()),
+            new_contributors: rel
+                .new_contributors
+                .iter()
+                .map(|contributor| ResolvedNewContributor {
+                    author: ResolvedAuthor::new(rel, &contributor.author),
+                    url: rel.contribution_url(contributor),
+                })
+                .collect(),
+            sections: ResolvedSection::all(rel, rel),
+            commits: ResolvedCommit::all(rel, rel),
+            components: rel
+                .components
+                .iter()
+                .map(|component| ResolvedComponent {
+                    name: component.name.clone(),
+                    repo_url: component.release.repo_url.clone(),
+                    sections: ResolvedSection::all(rel, &component.release),
+                    commits: ResolvedCommit::all(rel, &component.release),
+                })
+                .collect(),
+        }
+    }
+}
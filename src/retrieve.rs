@@ -0,0 +1,1093 @@
+use crate::cache::{ApiCache, Cache};
+use crate::categorize::{self, Categorized};
+use crate::config::{Categorization, Config};
+use crate::git::{repo_root, Branches, Commit, CommitGraph, Repository};
+use crate::release::{
+    self, normalize_url, Author, Change, Commit as ReleaseCommit, Component, Forge, NewContributor,
+    OneOrMore, PullRequest, Release, Section,
+};
+use crate::{github, gitlab, Result};
+
+use chrono::{DateTime, FixedOffset};
+use tracing::{debug, info};
+
+use std::cell::OnceCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// The number of walked commits from which on a missing commit-graph is hinted at.
+const LARGE_HISTORY: usize = 10_000;
+
+/// The options of retrieving a release, as given to the `retrieve` subcommand.
+///
+/// The commits are retrieved from the repository at `path`, or via the API of GitHub or GitLab
+/// if `github` or `gitlab` is present, starting at the revision `from`.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Path to a directory of the repository.
+    pub path: PathBuf,
+    /// A revision or a range of revisions to retrieve the commits of, instead of a branch.
+    pub range: Option<String>,
+    /// The branch to retrieve the commits from, overriding the configuration's.
+    pub branch: Option<String>,
+    /// The remote whose branch is retrieved, overriding the configuration's.
+    pub remote: Option<String>,
+    /// The URL to the repository, overriding the configuration's and the remote's.
+    pub repo_url: Option<String>,
+    /// Only retrieve from local branches.
+    pub local: bool,
+    /// Only retrieve from the remote-tracking branches of the remote.
+    pub tracking: bool,
+    /// Fetch the branch and the tags from the remote before retrieving the commits.
+    pub fetch: bool,
+    /// A commit hash to define the start boundary of the list.
+    pub start: Option<String>,
+    /// A commit hash to define the (inclusive) end boundary of the list.
+    pub end: Option<String>,
+    /// Revisions whose reachable commits are excluded.
+    pub exclude: Vec<String>,
+    /// Exclude the commits of bots, in addition to the configuration.
+    pub exclude_bots: bool,
+    /// Patterns of paths, of which commits must change one, if not empty.
+    pub paths: Vec<String>,
+    /// Skip merge commits.
+    pub no_merges: bool,
+    /// Only follow the first parent of merge commits.
+    pub first_parent: bool,
+    /// Create one change per merged pull request, rather than per commit.
+    pub prs: bool,
+    /// Name the authors of commits by the logins of their GitHub accounts.
+    pub github_logins: bool,
+    /// List the authors whose first commit in the repository is among the commits.
+    pub new_contributors: bool,
+    /// A tag whose commits are excluded.
+    pub from_tag: Option<String>,
+    /// Retrieve the commits since the most recent tag reachable from the branch.
+    pub since_last_tag: bool,
+    /// A tag to retrieve the commits up to, instead of the whole branch.
+    pub to_tag: Option<String>,
+    /// A GitHub repository, as `owner/name`, to retrieve the commits of via the API.
+    pub github: Option<String>,
+    /// The URL of a GitHub Enterprise Server, overriding the configuration's.
+    pub web_url: Option<String>,
+    /// The URL of the REST API of a GitHub Enterprise Server, overriding the configuration's.
+    pub api_url: Option<String>,
+    /// A GitLab project, as `group/name`, to retrieve the commits of via the API.
+    pub gitlab: Option<String>,
+    /// The URL of the GitLab instance.
+    pub gitlab_url: String,
+    /// The revision to define the (exclusive) start of the commits retrieved via an API.
+    pub from: Option<String>,
+    /// The revision to define the end of the commits retrieved via an API, instead
+    /// of the branch.
+    pub to: Option<String>,
+    /// Credit the users that approved the pull requests of the changes as reviewers.
+    pub reviewers: bool,
+    /// Categorize the changes by the labels of their pull requests.
+    pub labels: bool,
+    /// Name the changes by the release notes in the descriptions of their pull requests.
+    pub release_notes: bool,
+    /// The maximum number of concurrent requests to the GitHub API.
+    pub jobs: usize,
+    /// Path to the configuration file, instead of the one in the root of the repository.
+    pub config: Option<PathBuf>,
+    /// The notes reference to read overrides of the categorization of commits from.
+    pub notes_ref: String,
+    /// Only retrieve commits committed at or after a point in time.
+    pub since: Option<DateTime<FixedOffset>>,
+    /// Only retrieve commits committed before a point in time.
+    pub until: Option<DateTime<FixedOffset>>,
+    /// Neither read nor write the caches of commits and of GitHub API lookups.
+    pub no_cache: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("."),
+            range: None,
+            branch: None,
+            remote: None,
+            repo_url: None,
+            local: false,
+            tracking: false,
+            fetch: false,
+            start: None,
+            end: None,
+            exclude: Vec::new(),
+            exclude_bots: false,
+            paths: Vec::new(),
+            no_merges: false,
+            first_parent: false,
+            prs: false,
+            github_logins: false,
+            new_contributors: false,
+            from_tag: None,
+            since_last_tag: false,
+            to_tag: None,
+            github: None,
+            web_url: None,
+            api_url: None,
+            gitlab: None,
+            gitlab_url: gitlab::URL.to_string(),
+            from: None,
+            to: None,
+            reviewers: false,
+            labels: false,
+            release_notes: false,
+            jobs: 8,
+            config: None,
+            notes_ref: categorize::NOTES_REF.to_string(),
+            since: None,
+            until: None,
+            no_cache: false,
+        }
+    }
+}
+
+/// Categorize a commit into a change of a section, or `None` if the commit is excluded.
+///
+/// The categorization is cached, and the co-authors of the commit are credited alongside its author.
+fn commit_change(commit: &Commit, cache: &mut Cache) -> Result<Option<(Section, Change)>> {
+    let note = commit.note.as_deref();
+
+    let categorized = match cache.categorized(&commit.hash, note) {
+        Some(categorized) => categorized.clone(),
+        None => {
+            let mut categorized = Categorized::new(commit.message.as_str());
+            categorized.apply_conventional();
+            categorized.apply_trailers(&commit.body);
+
+            if let Some(note) = note {
+                categorized.apply_note(note);
+            }
+
+            cache.set_categorized(&commit.hash, note, categorized.clone());
+            categorized
+        }
+    };
+
+    let section = match categorized.section {
+        Some(section) => section,
+        None => {
+            debug!("skipping commit {}: its message excludes it", commit.hash);
+            return Ok(None);
+        }
+    };
+
+    let mut change = Change::new(
+        categorized.category,
+        categorized.name,
+        Author::from_identity(&commit.author.name, &commit.author.email),
+        &commit.hash,
+    )?;
+
+    let OneOrMore(authors) = &mut change.2;
+
+    for co_author in commit.co_authors() {
+        let author = Author::from_identity(&co_author.name, &co_author.email);
+
+        if co_author.email != commit.author.email && !authors.contains(&author) {
+            authors.push(author);
+        }
+    }
+
+    Ok(Some((section, change)))
+}
+
+/// Find the authors of commits whose first commit in the repository is among them,
+/// with their first commit.
+fn new_contributors(repo: &Repository, commits: &[Commit]) -> Result<Vec<(Author, ReleaseCommit)>> {
+    let hashes = commits.iter().map(|c| c.hash.clone()).collect::<Vec<_>>();
+    let mut known = repo.prior_authors(&hashes)?;
+    let mut contributors = Vec::new();
+
+    // The commits are listed newest first.
+    for commit in commits.iter().rev() {
+        if known.insert(commit.author.email.to_lowercase()) {
+            contributors.push((
+                Author::from_identity(&commit.author.name, &commit.author.email),
+                ReleaseCommit::new(&commit.hash)?,
+            ));
+        }
+    }
+
+    Ok(contributors)
+}
+
+/// Generate a release with one change per commit, excluding the commits whose messages
+/// exclude them.
+pub fn generate_release(
+    repo_url: String,
+    commits: impl Iterator<Item = Commit>,
+    cache: &mut Cache,
+) -> Result<Release> {
+    let mut release = Release {
+        repo_url: normalize_url(&repo_url)?,
+        ..Default::default()
+    };
+
+    for commit in commits {
+        if let Some((section, change)) = commit_change(&commit, cache)? {
+            release.section_mut(section).push(change);
+        }
+    }
+
+    Ok(release)
+}
+
+/// Categorize the merge commit of a pull request into a change of a section, named by
+/// the title of the pull request, with the authors and commits of the merged branch.
+fn pull_request_change(
+    merge: &Commit,
+    number: u64,
+    title: &str,
+    branch: &[Commit],
+) -> Result<Option<(Section, Change)>> {
+    let mut categorized = Categorized::new(format!("{} (#{})", title, number));
+    categorized.apply_conventional();
+    categorized.apply_trailers(&merge.body);
+
+    if let Some(note) = &merge.note {
+        categorized.apply_note(note);
+    }
+
+    let section = match categorized.section {
+        Some(section) => section,
+        None => return Ok(None),
+    };
+
+    let mut authors = Vec::new();
+
+    for commit in branch {
+        let author = Author::from_identity(&commit.author.name, &commit.author.email);
+
+        if !authors.contains(&author) {
+            authors.push(author);
+        }
+    }
+
+    if authors.is_empty() {
+        authors.push(Author::from_identity(
+            &merge.author.name,
+            &merge.author.email,
+        ));
+    }
+
+    let commits = match branch {
+        [] => vec![ReleaseCommit::new(&merge.hash)?],
+        branch => branch
+            .iter()
+            .map(|c| ReleaseCommit::new(&c.hash))
+            .collect::<Result<_, _>>()?,
+    };
+
+    let change = Change(
+        categorized.category,
+        categorized.name,
+        OneOrMore(authors),
+        OneOrMore(commits),
+        None,
+    );
+
+    Ok(Some((section, change)))
+}
+
+/// Generate a release with one change per merged pull request, rather than per commit.
+///
+/// The commits of branches merged by pull requests are attached to the changes of the pull
+/// requests, and changes referencing the same pull request, such as `Add a feature (#123)`,
+/// are joined. Commits outside of pull requests remain changes of their own.
+fn generate_pull_request_release(
+    repo_url: String,
+    repo: &Repository,
+    commits: impl Iterator<Item = Commit>,
+    cache: &mut Cache,
+) -> Result<Release> {
+    let mut release = Release {
+        repo_url: normalize_url(&repo_url)?,
+        ..Default::default()
+    };
+
+    let commits = commits.collect::<Vec<_>>();
+
+    // The commits of the merged branches of pull requests, by the hash of their merge commits.
+    let mut branches = HashMap::new();
+    let mut merged = HashSet::new();
+
+    for commit in &commits {
+        if commit.merged_pull_request().is_some() {
+            let branch = repo.merged_commits(&commit.hash)?;
+            merged.extend(branch.iter().map(|c| c.hash.clone()));
+            branches.insert(commit.hash.clone(), branch);
+        }
+    }
+
+    // The section and index of the change of every pull request.
+    let mut pull_requests = HashMap::new();
+
+    for commit in &commits {
+        if merged.contains(&commit.hash) {
+            continue;
+        }
+
+        let categorized = match (commit.merged_pull_request(), branches.get(&commit.hash)) {
+            (Some((number, title)), Some(branch)) => {
+                pull_request_change(commit, number, title, branch)?
+            }
+            _ => commit_change(commit, cache)?,
+        };
+
+        let (section, change) = match categorized {
+            Some(categorized) => categorized,
+            None => continue,
+        };
+
+        if let Some(PullRequest(number)) = change.pull_request() {
+            if let Some(&(section, index)) = pull_requests.get(&number) {
+                let existing: &mut Change = &mut release.section_mut(section)[index];
+                let Change(_, _, OneOrMore(authors), OneOrMore(commits), _) = change;
+
+                for author in authors {
+                    if !(existing.2).0.contains(&author) {
+                        (existing.2).0.push(author);
+                    }
+                }
+
+                (existing.3).0.extend(commits);
+                continue;
+            }
+
+            pull_requests.insert(number, (section, release.section(section).len()));
+        }
+
+        release.section_mut(section).push(change);
+    }
+
+    Ok(release)
+}
+
+/// Rename the categories of the changes of a release and its components.
+fn rename_categories(release: &mut Release, categories: &BTreeMap<String, String>) {
+    if categories.is_empty() {
+        return;
+    }
+
+    for Change(category, ..) in release.iter_mut() {
+        if let Some(renamed) = categories.get(category.as_str()) {
+            *category = renamed.clone();
+        }
+    }
+
+    for component in &mut release.components {
+        rename_categories(&mut component.release, categories);
+    }
+}
+
+/// Retrieve the release of the commits of a repository, as defined by the options.
+///
+/// Without `dry_run`, the caches of commits and of responses of the GitHub API are saved.
+pub fn retrieve_release(opts: Options, dry_run: bool) -> Result<Release> {
+    if opts.fetch && dry_run {
+        return Err(
+            "`--fetch` cannot be used with `--dry-run`, as it updates the repository".into(),
+        );
+    }
+
+    let root = repo_root(&opts.path);
+    let mut config = Config::discover(&root, opts.config.as_deref())?;
+    config.retrieve.exclude_bots |= opts.exclude_bots;
+
+    let mut cache = match Repository::discover(&opts.path) {
+        Ok(repo) if !opts.no_cache => Cache::open(repo.git_dir()),
+        _ => Cache::disabled(),
+    };
+
+    let instance = config
+        .github
+        .instance(opts.web_url.as_deref(), opts.api_url.as_deref());
+
+    // The client is only created once it is needed, as discovering its token may run `gh`.
+    let github_client = OnceCell::new();
+    let github_client =
+        || github_client.get_or_init(|| config.github.client(instance.clone(), None));
+
+    let mut api_cache = if opts.no_cache {
+        ApiCache::disabled()
+    } else {
+        ApiCache::open()
+    };
+
+    let branch = opts.branch.or_else(|| config.retrieve.branch.clone());
+    let remote = opts
+        .remote
+        .or_else(|| config.retrieve.remote.clone())
+        .unwrap_or_else(|| "origin".to_string());
+    let branches = if opts.local {
+        Branches::Local
+    } else if opts.tracking || opts.fetch {
+        // The fetched remote-tracking branch is retrieved, rather than a stale local branch.
+        Branches::Remote
+    } else {
+        Branches::Any
+    };
+
+    let (since, until) = (opts.since, opts.until);
+    let defaults = &config.retrieve;
+    let in_time = move |commit: &Commit| {
+        let skipped = if since.is_some_and(|since| commit.time < since) {
+            Some("it is older than `--since`")
+        } else if until.is_some_and(|until| commit.time >= until) {
+            Some("it is not older than `--until`")
+        } else if defaults.is_excluded(&commit.author.name, &commit.author.email) {
+            Some("its author is excluded")
+        } else {
+            None
+        };
+
+        if let Some(reason) = skipped {
+            debug!("skipping commit {}: {}", commit.hash, reason);
+        }
+
+        skipped.is_none()
+    };
+
+    let mut release = if let Some(repo) = opts.github {
+        let client = github_client();
+        let from = opts.from.ok_or("retrieving via an API requires `--from`")?;
+        // The APIs resolve `HEAD` to the default branch of the repository.
+        let to = opts.to.or(branch).unwrap_or_else(|| "HEAD".to_string());
+
+        let commits = client.compare(&repo, &from, &to)?;
+        generate_release(
+            client.instance().repo_url(&repo),
+            commits.into_iter().filter(in_time),
+            &mut cache,
+        )?
+    } else if let Some(project) = opts.gitlab {
+        let client = gitlab::Client::new(&opts.gitlab_url, gitlab_token());
+        let from = opts.from.ok_or("retrieving via an API requires `--from`")?;
+        // The APIs resolve `HEAD` to the default branch of the repository.
+        let to = opts.to.or(branch).unwrap_or_else(|| "HEAD".to_string());
+
+        let commits = client.compare(&project, &from, &to)?;
+        let mut release = generate_release(
+            client.project_url(&project),
+            commits.into_iter().filter(in_time),
+            &mut cache,
+        )?;
+
+        // Self-hosted instances are not necessarily detected as GitLab by their URL.
+        release.forge = Some(Forge::Gitlab);
+        release
+    } else if !config.repositories.is_empty() {
+        if opts.range.is_some() || opts.repo_url.is_some() {
+            return Err(
+                "a range and `--repo-url` are not available when aggregating multiple repositories"
+                    .into(),
+            );
+        }
+
+        if opts.release_notes || opts.labels || opts.reviewers || opts.new_contributors {
+            return Err(
+                "`--release-notes`, `--labels`, `--reviewers` and `--new-contributors` are not \
+                 available when aggregating multiple repositories"
+                    .into(),
+            );
+        }
+
+        // Revisions and tags are those of a single repository.
+        if opts.start.is_some()
+            || opts.end.is_some()
+            || !opts.exclude.is_empty()
+            || opts.from_tag.is_some()
+            || opts.to_tag.is_some()
+        {
+            return Err(
+                "`--start`, `--end`, `--exclude`, `--from-tag` and `--to-tag` are not available \
+                 when aggregating multiple repositories; define `start`, `end` and `exclude` \
+                 for each repository in the configuration"
+                    .into(),
+            );
+        }
+
+        let mut components = Vec::with_capacity(config.repositories.len());
+        let mut hash_length = release::DEFAULT_HASH_LENGTH;
+
+        for repo_config in &config.repositories {
+            let path = root.join(&repo_config.path);
+            let repo = Repository::open(&path)
+                .map_err(|err| format!("{}: {}", path.display(), err))?
+                .with_remote(&remote)
+                .with_branches(branches);
+            let branch = repo_config.branch.as_ref().or(branch.as_ref());
+
+            if opts.fetch {
+                repo.fetch(branch.map(String::as_str))?;
+            }
+
+            let branch = match branch {
+                Some(branch) => branch.clone(),
+                None => repo.default_branch()?,
+            };
+            let mut commits = repo
+                .commits(&branch)?
+                .notes(&opts.notes_ref)
+                .paths(&opts.paths);
+
+            if let Some(start) = &repo_config.start {
+                commits = commits.start(start)?;
+            }
+
+            if opts.since_last_tag {
+                if let Some(tag) = commits.last_tag()? {
+                    info!(
+                        "retrieving the commits of {} since {}",
+                        repo_config.name, tag
+                    );
+                    commits = commits.exclude(&repo.tag(&tag)?)?;
+                }
+            }
+
+            if let Some(end) = &repo_config.end {
+                commits = commits.end(end)?;
+            }
+
+            for rev in &repo_config.exclude {
+                commits = commits.exclude(rev)?;
+            }
+
+            if opts.no_merges {
+                commits = commits.no_merges();
+            }
+
+            if opts.first_parent {
+                commits = commits.first_parent()?;
+            }
+
+            let mut walked = 0;
+            let mut commits = commits
+                .inspect(|_| walked += 1)
+                .collect::<Result<Vec<_>, _>>()?;
+            commits.retain(in_time);
+
+            let repo_url = repo.url()?;
+
+            if opts.github_logins {
+                resolve_logins(
+                    github_client(),
+                    &repo_url,
+                    &mut commits,
+                    &mut api_cache,
+                    opts.jobs,
+                )?;
+            }
+
+            let release = if opts.prs {
+                generate_pull_request_release(repo_url, &repo, commits.into_iter(), &mut cache)?
+            } else {
+                generate_release(repo_url, commits.into_iter(), &mut cache)?
+            };
+
+            // The hashes of all components are abbreviated alike, to the longest length needed.
+            let len = repo.abbreviation_length(release.commits().map(ReleaseCommit::hash))?;
+            hash_length = hash_length.max(len);
+
+            if walked >= LARGE_HISTORY && repo.commit_graph() != CommitGraph::Usable {
+                hint_commit_graph(&repo);
+            }
+
+            components.push(Component {
+                name: repo_config.name.clone(),
+                release,
+            });
+        }
+
+        // The release itself is rooted at the first repository, for the profiles of authors.
+        Release {
+            repo_url: components[0].release.repo_url.clone(),
+            components,
+            hash_length: Some(hash_length).filter(|&len| len > release::DEFAULT_HASH_LENGTH),
+            ..Default::default()
+        }
+    } else {
+        let repo = Repository::discover(&opts.path)?
+            .with_remote(&remote)
+            .with_branches(branches);
+
+        if opts.fetch {
+            // The branches of a range are unknown, so all branches are fetched.
+            let branch = match &opts.range {
+                Some(_) => None,
+                None => branch.as_deref(),
+            };
+
+            repo.fetch(branch)?;
+        }
+
+        let commits = match (&opts.range, branch) {
+            (Some(range), _) => repo.revisions(range)?,
+            (None, Some(branch)) => repo.commits(&branch)?,
+            (None, None) => repo.commits(&repo.default_branch()?)?,
+        };
+        let mut commits = commits.notes(&opts.notes_ref).paths(&opts.paths);
+
+        if let Some(start) = opts.start {
+            commits = commits.start(&start)?;
+        }
+
+        if let Some(tag) = &opts.to_tag {
+            commits = commits.start(&repo.tag(tag)?)?;
+        }
+
+        // The release is the one of the tag it ends at, if any.
+        let target = match &opts.to_tag {
+            Some(tag) => repo.tags(tag)?.into_iter().find(|t| t.name == *tag),
+            None => None,
+        };
+
+        // The tag of the previous release, that the commits are retrieved since.
+        let mut previous = None;
+
+        if let Some(tag) = &opts.from_tag {
+            commits = commits.exclude(&repo.tag(tag)?)?;
+            previous = Some(tag.clone());
+        }
+
+        if opts.since_last_tag {
+            if let Some(tag) = commits.last_tag()? {
+                info!("retrieving the commits since {}", tag);
+                commits = commits.exclude(&repo.tag(&tag)?)?;
+                previous = Some(tag);
+            }
+        }
+
+        if let Some(end) = opts.end {
+            commits = commits.end(&end)?;
+        }
+
+        for rev in &opts.exclude {
+            commits = commits.exclude(rev)?;
+        }
+
+        if opts.no_merges {
+            commits = commits.no_merges();
+        }
+
+        if opts.first_parent {
+            commits = commits.first_parent()?;
+        }
+
+        let mut walked = 0;
+        let mut commits = commits
+            .inspect(|_| walked += 1)
+            .collect::<Result<Vec<_>, _>>()?;
+        commits.retain(in_time);
+
+        let repo_url = match opts.repo_url.or_else(|| config.retrieve.repo_url.clone()) {
+            Some(url) => url,
+            None => repo.url().map_err(|err| {
+                format!(
+                    "{}, or give the URL to the repository with `--repo-url`",
+                    err
+                )
+            })?,
+        };
+
+        if opts.github_logins {
+            resolve_logins(
+                github_client(),
+                &repo_url,
+                &mut commits,
+                &mut api_cache,
+                opts.jobs,
+            )?;
+        }
+
+        let new_contributors = if opts.new_contributors {
+            new_contributors(&repo, &commits)?
+        } else {
+            Vec::new()
+        };
+
+        let mut release = if opts.prs {
+            generate_pull_request_release(repo_url, &repo, commits.into_iter(), &mut cache)?
+        } else {
+            generate_release(repo_url, commits.into_iter(), &mut cache)?
+        };
+
+        release.new_contributors = new_contributors
+            .into_iter()
+            .map(|(author, commit)| {
+                // The pull request of the change that lists the commit, if any.
+                let pull_request = release
+                    .iter()
+                    .find(|change| (change.3).0.contains(&commit))
+                    .and_then(Change::pull_request)
+                    .map(|PullRequest(number)| number);
+
+                NewContributor {
+                    author,
+                    commit,
+                    pull_request,
+                }
+            })
+            .collect();
+
+        if let Some(tag) = target {
+            release.date = Some(tag.time.format("%Y-%m-%d").to_string());
+            release.version = Some(tag.name);
+        }
+
+        release.previous_version = previous;
+
+        // Abbreviations are lengthened in large repositories, where they would be ambiguous.
+        let len = repo.abbreviation_length(release.commits().map(ReleaseCommit::hash))?;
+
+        if len > release::DEFAULT_HASH_LENGTH {
+            release.hash_length = Some(len);
+        }
+
+        if walked >= LARGE_HISTORY && repo.commit_graph() != CommitGraph::Usable {
+            hint_commit_graph(&repo);
+        }
+
+        release
+    };
+
+    config.gitea.detect(&mut release);
+    rename_categories(&mut release, &config.retrieve.categories);
+
+    if opts.release_notes {
+        apply_release_notes(github_client(), &mut release, &mut api_cache, opts.jobs)?;
+    }
+
+    if opts.labels {
+        categorize_by_labels(
+            github_client(),
+            &mut release,
+            &config,
+            &mut api_cache,
+            opts.jobs,
+        )?;
+    }
+
+    if opts.reviewers {
+        retrieve_reviewers(github_client(), &mut release, &mut api_cache, opts.jobs)?;
+    }
+
+    if !dry_run {
+        cache.save()?;
+        api_cache.save()?;
+    }
+
+    Ok(release)
+}
+
+/// Hint at writing a commit-graph for a repository, without which walking large histories is slow.
+fn hint_commit_graph(repo: &Repository) {
+    eprintln!(
+        "hint: write a commit-graph to speed up retrieving from large histories with \
+         `git -C {} -c commitGraph.generationVersion=1 commit-graph write --reachable`",
+        repo.root().display()
+    );
+}
+
+/// Returns the numbers of the pull requests of a change.
+///
+/// The pull request of a change is determined by its name, or if it does not reference one,
+/// by the pull requests that introduced its commits.
+fn pull_requests(
+    client: &github::Client,
+    repo: &str,
+    change: &Change,
+    cache: &mut ApiCache,
+) -> Result<Vec<u64>> {
+    if let Some(pr) = change.pull_request() {
+        return Ok(vec![pr.0]);
+    }
+
+    let mut prs = Vec::new();
+
+    for commit in &(change.3).0 {
+        match cache.pull_requests(repo, commit.hash()) {
+            Some(cached) => prs.extend_from_slice(cached),
+            None => {
+                let found = client.pull_requests(repo, commit.hash())?;
+                prs.extend_from_slice(&found);
+                cache.set_pull_requests(repo, commit.hash(), found);
+            }
+        }
+    }
+
+    Ok(prs)
+}
+
+/// Find the pull requests that introduced the commits of changes that do not reference one,
+/// concurrently, and cache them for [`pull_requests`].
+fn prefetch_pull_requests(
+    client: &github::Client,
+    repo: &str,
+    release: &Release,
+    cache: &mut ApiCache,
+    jobs: usize,
+) -> Result<()> {
+    let mut seen = HashSet::new();
+    let hashes = release
+        .iter()
+        .filter(|change| change.pull_request().is_none())
+        .flat_map(|change| &(change.3).0)
+        .map(ReleaseCommit::hash)
+        .filter(|hash| cache.pull_requests(repo, hash).is_none() && seen.insert(*hash))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+
+    let found = concurrently(jobs, &hashes, |hash| client.pull_requests(repo, hash))?;
+
+    for (hash, prs) in hashes.iter().zip(found) {
+        cache.set_pull_requests(repo, hash, prs);
+    }
+
+    Ok(())
+}
+
+/// Returns the numbers of the pull requests of all changes of the release, without duplicates.
+fn all_pull_requests(
+    client: &github::Client,
+    repo: &str,
+    release: &Release,
+    cache: &mut ApiCache,
+    jobs: usize,
+) -> Result<Vec<u64>> {
+    prefetch_pull_requests(client, repo, release, cache, jobs)?;
+
+    let mut prs = Vec::new();
+
+    for change in release.iter() {
+        prs.extend(pull_requests(client, repo, change, cache)?);
+    }
+
+    prs.sort_unstable();
+    prs.dedup();
+
+    Ok(prs)
+}
+
+/// Call `f` with each of the `items` on up to `jobs` threads, such as to issue requests
+/// to an API concurrently, returning the results in the order of the items.
+///
+/// Fails with the first error, by the order of the items.
+fn concurrently<T, R, F>(jobs: usize, items: &[T], f: F) -> Result<Vec<R>>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> Result<R> + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            scope.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+
+                    let item = match items.get(i) {
+                        Some(item) => item,
+                        None => break,
+                    };
+
+                    // Errors are not `Send`, so they are passed on as their messages.
+                    let res = f(item).map_err(|err| err.to_string());
+                    results.lock().unwrap().push((i, res));
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(i, _)| *i);
+
+    results
+        .into_iter()
+        .map(|(_, res)| res.map_err(Into::into))
+        .collect()
+}
+
+/// The token to authenticate to the GitLab API with, if any.
+fn gitlab_token() -> Option<String> {
+    std::env::var("RELEASE_MAKER_GITLAB_TOKEN")
+        .or_else(|_| std::env::var("GITLAB_TOKEN"))
+        .ok()
+}
+
+fn github_repo(client: &github::Client, release: &Release, feature: &str) -> Result<String> {
+    let instance = client.instance();
+
+    match instance.repo_from_url(&release.repo_url) {
+        Some(repo) => Ok(repo.to_string()),
+        None => Err(format!(
+            "{} is only available for repositories on {}",
+            feature, instance.web_url
+        )
+        .into()),
+    }
+}
+
+/// Name the authors of commits by the logins of their GitHub accounts, as found by their
+/// `users.noreply.github.com` emails or via the GitHub API.
+fn resolve_logins(
+    client: &github::Client,
+    repo_url: &str,
+    commits: &mut [Commit],
+    cache: &mut ApiCache,
+    jobs: usize,
+) -> Result<()> {
+    let repo_url = normalize_url(repo_url)?;
+    let instance = client.instance();
+    let repo = instance.repo_from_url(&repo_url).ok_or_else(|| {
+        format!(
+            "resolving logins is only available for repositories on {}",
+            instance.web_url
+        )
+    })?;
+
+    // The login of each author is looked up by one of their commits.
+    let mut seen = HashSet::new();
+    let lookups = commits
+        .iter()
+        .filter(|commit| instance.noreply_login(&commit.author.email).is_none())
+        .filter(|commit| cache.login(repo, &commit.author.email).is_none())
+        .filter(|commit| seen.insert(commit.author.email.as_str()))
+        .map(|commit| (commit.author.email.clone(), commit.hash.clone()))
+        .collect::<Vec<_>>();
+
+    let logins = concurrently(jobs, &lookups, |(_, hash)| client.author_login(repo, hash))?;
+
+    for ((email, _), login) in lookups.iter().zip(logins) {
+        cache.set_login(repo, email, login);
+    }
+
+    for commit in commits {
+        let login = match instance.noreply_login(&commit.author.email) {
+            Some(login) => Some(login.to_string()),
+            None => cache
+                .login(repo, &commit.author.email)
+                .flatten()
+                .map(str::to_string),
+        };
+
+        if let Some(login) = login {
+            commit.author.name = login;
+        }
+    }
+
+    Ok(())
+}
+
+/// Rename the changes of the release by the release notes of their pull requests,
+/// and exclude those whose release note is `NONE`.
+fn apply_release_notes(
+    client: &github::Client,
+    release: &mut Release,
+    cache: &mut ApiCache,
+    jobs: usize,
+) -> Result<()> {
+    let repo = github_repo(client, release, "retrieving release notes")?;
+
+    let prs = all_pull_requests(client, &repo, release, cache, jobs)?;
+    let bodies = concurrently(jobs, &prs, |&pr| client.pull_request_body(&repo, pr))?;
+    let bodies = prs.into_iter().zip(bodies).collect::<HashMap<_, _>>();
+
+    for section in Section::ALL {
+        let changes = std::mem::take(release.section_mut(section));
+
+        for mut change in changes {
+            let mut note = None;
+
+            for pr in pull_requests(client, &repo, &change, cache)? {
+                let body = bodies[&pr].as_deref().unwrap_or_default();
+
+                if let Some(n) = github::release_note(body) {
+                    note = Some((pr, n));
+                    break;
+                }
+            }
+
+            match note {
+                Some((_, github::ReleaseNote::None)) => continue,
+                Some((pr, github::ReleaseNote::Text(text))) => {
+                    change.1 = format!("{} (#{})", text, pr);
+                }
+                None => {}
+            }
+
+            release.section_mut(section).push(change);
+        }
+    }
+
+    Ok(())
+}
+
+/// Move the changes of the release into sections by the labels of their pull requests.
+fn categorize_by_labels(
+    client: &github::Client,
+    release: &mut Release,
+    config: &Config,
+    cache: &mut ApiCache,
+    jobs: usize,
+) -> Result<()> {
+    let repo = github_repo(client, release, "categorizing by labels")?;
+
+    let prs = all_pull_requests(client, &repo, release, cache, jobs)?;
+    let labels_of = concurrently(jobs, &prs, |&pr| client.labels(&repo, pr))?;
+    let labels_of = prs.into_iter().zip(labels_of).collect::<HashMap<_, _>>();
+
+    for section in Section::ALL {
+        let changes = std::mem::take(release.section_mut(section));
+
+        for change in changes {
+            let mut labels = Vec::new();
+
+            for pr in pull_requests(client, &repo, &change, cache)? {
+                labels.extend_from_slice(&labels_of[&pr]);
+            }
+
+            match config.labels.categorize(&labels) {
+                Some(Categorization::Exclude) => {}
+                Some(Categorization::Section(target)) => release.section_mut(target).push(change),
+                None => release.section_mut(section).push(change),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Add the users that approved the pull requests of the release's changes as its reviewers.
+fn retrieve_reviewers(
+    client: &github::Client,
+    release: &mut Release,
+    cache: &mut ApiCache,
+    jobs: usize,
+) -> Result<()> {
+    let repo = github_repo(client, release, "retrieving reviewers")?;
+
+    let prs = all_pull_requests(client, &repo, release, cache, jobs)?;
+    let approvers = concurrently(jobs, &prs, |&pr| client.approvers(&repo, pr))?;
+
+    for logins in approvers {
+        for login in logins {
+            let reviewer = Author::new(login);
+
+            if !release.reviewers.contains(&reviewer) {
+                release.reviewers.push(reviewer);
+            }
+        }
+    }
+
+    Ok(())
+}
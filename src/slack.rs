@@ -0,0 +1,197 @@
+use crate::release::{self, Author, AuthorLink, Change, OneOrMore, PullRequest, Release};
+use crate::{http, Result};
+
+use serde_json::json;
+
+use std::fmt;
+
+/// Escapes the characters that are significant in Slack's mrkdwn: `&`, `<` and `>`.
+struct Escape<'a>(&'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn write_author(source: &mut dyn fmt::Write, rel: &Release, author: &Author) -> fmt::Result {
+    match rel.get_author_link(author) {
+        Some(AuthorLink::Profile(url)) => {
+            write!(source, "<{}|@{}>", Escape(&url), Escape(author.name()))
+        }
+        Some(AuthorLink::Mail(url)) => {
+            write!(source, "<{}|{}>", Escape(&url), Escape(author.name()))
+        }
+        None => write!(source, "{}", Escape(author.name())),
+    }
+}
+
+/// Write a list of changes of `part`, which is either the release `rel` or one of its components,
+/// under a header.
+fn write_list(
+    source: &mut dyn fmt::Write,
+    header: &str,
+    rel: &Release,
+    part: &Release,
+    changes: &[Change],
+) -> fmt::Result {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(source, "*{}*", Escape(header))?;
+
+    for change in changes {
+        let Change(category, name, OneOrMore(authors), OneOrMore(commits), _) = change;
+
+        let (name, pr) = PullRequest::split(name);
+
+        write!(source, "• [{}] {} (", Escape(category), Escape(name))?;
+
+        for (i, author) in authors.iter().enumerate() {
+            if i != 0 {
+                source.write_str(" ")?;
+            }
+
+            write_author(source, rel, author)?;
+        }
+
+        source.write_str(")")?;
+
+        for commit in commits {
+            write!(
+                source,
+                " <{}|`{}`>",
+                Escape(&part.commit_url(commit.hash())),
+                Escape(rel.short_hash(commit))
+            )?;
+        }
+
+        if let Some(PullRequest(number)) = pr {
+            write!(
+                source,
+                " <{}|{}>",
+                Escape(&part.pull_request_url(number)),
+                part.pull_request_ref(number)
+            )?;
+        }
+
+        writeln!(source)?;
+    }
+
+    writeln!(source)
+}
+
+/// Generate the output message from a [`Release`] in Slack's mrkdwn, by writing to a source
+/// implementing [`std::fmt::Write`]
+///
+/// Links are written as `<url|text>` and headings in bold, as `*heading*`. As mrkdwn has
+/// no lists, changes and contributors are written as lines beginning with bullets.
+///
+/// [`Release`]: ../release/struct.Release.html
+/// [`std::fmt::Write`]: std::fmt::Write
+pub fn generate_slack(source: &mut dyn fmt::Write, rel: &Release) -> fmt::Result {
+    if let Some(heading) = rel.heading() {
+        writeln!(source, "*{}*\n", Escape(&heading))?;
+    }
+
+    if let Some(intro) = &rel.intro {
+        writeln!(source, "{}\n", Escape(intro.trim_end()))?;
+    }
+
+    if let Some(summary) = &rel.summary {
+        writeln!(source, "{}\n", Escape(summary.trim_end()))?;
+    }
+
+    writeln!(source, "{}", Escape(rel.strings.contributors()))?;
+
+    for author in rel.sorted_authors() {
+        write!(source, "• ")?;
+        write_author(source, rel, author)?;
+        writeln!(source)?;
+    }
+
+    writeln!(source)?;
+
+    let mut reviewers = rel.reviewers.iter().collect::<Vec<_>>();
+    release::sort_authors(&mut reviewers);
+
+    if !reviewers.is_empty() {
+        writeln!(source, "{}", Escape(rel.strings.reviewers()))?;
+
+        for reviewer in &reviewers {
+            write!(source, "• ")?;
+            write_author(source, rel, reviewer)?;
+            writeln!(source)?;
+        }
+
+        writeln!(source)?;
+    }
+
+    for section in rel.sections() {
+        write_list(source, &section.heading, rel, rel, section.changes)?;
+    }
+
+    for component in &rel.components {
+        let part = &component.release;
+
+        for section in rel.sections_of(part) {
+            let header = format!("{}: {}", component.name, section.heading);
+            write_list(source, &header, rel, part, section.changes)?;
+        }
+    }
+
+    if !rel.new_contributors.is_empty() {
+        writeln!(source, "*{}*", Escape(rel.strings.new_contributors()))?;
+
+        for contributor in &rel.new_contributors {
+            write!(source, "• ")?;
+            write_author(source, rel, &contributor.author)?;
+            writeln!(
+                source,
+                " {} <{}>",
+                Escape(rel.strings.first_contribution()),
+                Escape(&rel.contribution_url(contributor))
+            )?;
+        }
+
+        writeln!(source)?;
+    }
+
+    if let Some(epilogue) = &rel.epilogue {
+        writeln!(source, "{}\n", Escape(epilogue.trim_end()))?;
+    }
+
+    if let Some(url) = rel.full_changelog_url() {
+        writeln!(
+            source,
+            "*{}*: <{}>",
+            Escape(rel.strings.full_changelog()),
+            Escape(&url)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Post a message to a Slack incoming webhook.
+pub fn post_webhook(url: &str, text: &str) -> Result<()> {
+    let req = ureq::post(url).set(
+        "User-Agent",
+        concat!("release-maker/", env!("CARGO_PKG_VERSION")),
+    );
+
+    http::send_json(req, json!({ "text": text }))
+        .map_err(|err| format!("failed to post to the webhook: {}", err))?;
+
+    Ok(())
+}
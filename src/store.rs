@@ -0,0 +1,140 @@
+use crate::release::Release;
+use crate::Result;
+
+use std::cmp::Ordering;
+use std::fs;
+use std::path::PathBuf;
+
+/// The default directory where releases are stored, relative to the root of the repository.
+pub const DEFAULT_DIR: &str = ".releases";
+
+/// Parse a version name, such as `v1.3.0` or `1.3.0`, as a semantic version.
+pub fn parse_version(version: &str) -> Option<semver::Version> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    semver::Version::parse(version).ok()
+}
+
+/// Compare two version names, ordering the newest version first.
+///
+/// Names that are not semantic versions are ordered after those that are, alphabetically.
+pub fn cmp_versions(a: &str, b: &str) -> Ordering {
+    match (parse_version(a), parse_version(b)) {
+        (Some(a), Some(b)) => b.cmp(&a),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// A directory of releases, where each release is stored as `<version>.json`.
+#[derive(Debug, Clone)]
+pub struct Store {
+    dir: PathBuf,
+}
+
+impl Store {
+    /// Create a store of releases in the `dir` directory.
+    pub fn new<P>(dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns the path to the file of the release of a version.
+    ///
+    /// # Errors
+    /// An error is returned if the version is empty, or contains path separators or `..`,
+    /// which would place the file outside of the directory of the store.
+    pub fn path(&self, version: &str) -> Result<PathBuf> {
+        if version.is_empty() || version.contains(['/', '\\']) || version.contains("..") {
+            return Err(format!(
+                "invalid version `{}`: versions must not contain path separators or `..`",
+                version
+            )
+            .into());
+        }
+
+        Ok(self.dir.join(format!("{}.json", version)))
+    }
+
+    /// Returns whether a release of a version is stored.
+    ///
+    /// Versions that cannot be stored, as they contain path separators, are never stored.
+    pub fn contains(&self, version: &str) -> bool {
+        self.path(version).is_ok_and(|path| path.is_file())
+    }
+
+    /// Load the release of a version.
+    pub fn load(&self, version: &str) -> Result<Release> {
+        let path = self.path(version)?;
+        let file = fs::File::open(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+        let mut release: Release = serde_json::from_reader(std::io::BufReader::new(file))
+            .map_err(|err| format!("{}: {}", path.display(), err))?;
+
+        release
+            .normalize_urls()
+            .map_err(|err| format!("{}: {}", path.display(), err))?;
+
+        Ok(release)
+    }
+
+    /// Store the release of a version, creating the directory if it does not exist.
+    ///
+    /// Returns the path to the file of the release.
+    pub fn save(&self, version: &str, release: &Release) -> Result<PathBuf> {
+        let path = self.path(version)?;
+        fs::create_dir_all(&self.dir)?;
+
+        fs::write(&path, serde_json::to_string_pretty(release)? + "\n")?;
+
+        Ok(path)
+    }
+
+    /// Returns the versions of all stored releases, newest first.
+    ///
+    /// If the directory does not exist, no versions are returned.
+    pub fn versions(&self) -> Result<Vec<String>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut versions = Vec::new();
+
+        for entry in entries {
+            let path = entry?.path();
+
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+
+            if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                versions.push(stem.to_string());
+            }
+        }
+
+        versions.sort_by(|a, b| cmp_versions(a, b));
+
+        Ok(versions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions() {
+        assert_eq!(parse_version("v1.3.0"), Some(semver::Version::new(1, 3, 0)));
+        assert_eq!(parse_version("1.3.0"), Some(semver::Version::new(1, 3, 0)));
+        assert_eq!(parse_version("next"), None);
+
+        let mut names = vec!["v0.9.0", "next", "v1.10.0", "v1.2.0", "alpha"];
+        names.sort_by(|a, b| cmp_versions(a, b));
+
+        assert_eq!(names, ["v1.10.0", "v1.2.0", "v0.9.0", "alpha", "next"]);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::config::Style;
+use crate::release::{Change, PullRequest, Release};
+
+use std::fmt;
+
+/// Describes a violation of the style of titles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// The title starts with a lowercase letter.
+    Lowercase,
+    /// The title ends with a period.
+    Period,
+    /// The title is longer than the maximum length.
+    TooLong(usize, usize),
+    /// The title starts with a redundant verb.
+    RedundantVerb(String),
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::Lowercase => f.write_str("starts with a lowercase letter"),
+            Issue::Period => f.write_str("ends with a period"),
+            Issue::TooLong(len, max) => write!(f, "is {} characters long, exceeding {}", len, max),
+            Issue::RedundantVerb(verb) => write!(f, "starts with the redundant verb \"{}\"", verb),
+        }
+    }
+}
+
+/// Check a title against the style.
+///
+/// The reference to a pull request at the end of the title is ignored.
+pub fn check(style: &Style, title: &str) -> Vec<Issue> {
+    let (title, _) = PullRequest::split(title);
+    let mut issues = Vec::new();
+
+    if title.chars().next().is_some_and(char::is_lowercase) {
+        issues.push(Issue::Lowercase);
+    }
+
+    if title.ends_with('.') && !title.ends_with("..") {
+        issues.push(Issue::Period);
+    }
+
+    let len = title.chars().count();
+
+    if len > style.max_length {
+        issues.push(Issue::TooLong(len, style.max_length));
+    }
+
+    let first = title.split_whitespace().next().unwrap_or("");
+
+    if let Some(verb) = style
+        .redundant_verbs
+        .iter()
+        .find(|verb| verb.eq_ignore_ascii_case(first))
+    {
+        issues.push(Issue::RedundantVerb(verb.clone()));
+    }
+
+    issues
+}
+
+/// Truncate a title to a maximum length in characters, ending it with an ellipsis.
+///
+/// The title is cut at the last word boundary, unless that would discard more than half of it.
+/// The reference to a pull request at the end of the title is kept, and does not count towards
+/// the length.
+pub fn truncate(title: &str, max: usize) -> Option<String> {
+    let (text, pr) = PullRequest::split(title);
+
+    if text.chars().count() <= max {
+        return None;
+    }
+
+    // Leave room for the ellipsis.
+    let end = text
+        .char_indices()
+        .nth(max.saturating_sub(1))
+        .map_or(text.len(), |(i, _)| i);
+    let mut cut = &text[..end];
+
+    if let Some(space) = cut.rfind(char::is_whitespace) {
+        if cut[..space].chars().count() >= max / 2 {
+            cut = &cut[..space];
+        }
+    }
+
+    let cut = cut.trim_end_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation());
+
+    Some(match pr {
+        Some(pr) => format!("{}\u{2026} (#{})", cut, pr.0),
+        None => format!("{}\u{2026}", cut),
+    })
+}
+
+/// Truncate the titles of all changes of a release and its components that are longer than
+/// the maximum length.
+///
+/// The full titles are kept as the descriptions of the changes, unless they already have one.
+pub fn truncate_titles(rel: &mut Release, max: usize) {
+    for Change(_, name, _, _, description) in rel.iter_mut() {
+        if let Some(short) = truncate(name, max) {
+            let full = std::mem::replace(name, short);
+            description.get_or_insert(full);
+        }
+    }
+
+    for component in &mut rel.components {
+        truncate_titles(&mut component.release, max);
+    }
+}
+
+/// Check the titles of all changes of a release and its components against the style.
+///
+/// Returns the name of the section, the title, and the issues of every change that violates
+/// the style.
+pub fn lint<'a>(style: &Style, rel: &'a Release) -> Vec<(&'a str, &'a str, Vec<Issue>)> {
+    let parts = std::iter::once(rel).chain(rel.components.iter().map(|c| &c.release));
+
+    parts
+        .flat_map(|part| {
+            part.sections().into_iter().flat_map(move |section| {
+                section.changes.iter().map(move |Change(_, name, ..)| {
+                    (section.name, name.as_str(), check(style, name))
+                })
+            })
+        })
+        .filter(|(_, _, issues)| !issues.is_empty())
+        .collect()
+}
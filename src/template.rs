@@ -0,0 +1,54 @@
+use crate::categorize::parse_section;
+use crate::resolved::ResolvedRelease;
+use crate::Result;
+
+use handlebars::{handlebars_helper, Handlebars};
+
+use std::path::Path;
+
+/// The name the template is registered under.
+const NAME: &str = "release";
+
+handlebars_helper!(heading: |name: str| {
+    match parse_section(name) {
+        Some(Some(section)) => section.heading(),
+        _ => name,
+    }.to_string()
+});
+
+/// A [Handlebars] template of the output, rendered with a [`ResolvedRelease`] as its context.
+///
+/// Besides the built-in helpers, `{{heading section}}` renders the heading of a section,
+/// such as `Added` for `added`. The output is not escaped.
+///
+/// [Handlebars]: https://handlebarsjs.com
+/// [`ResolvedRelease`]: ../resolved/struct.ResolvedRelease.html
+pub struct Template {
+    registry: Handlebars<'static>,
+}
+
+impl Template {
+    /// Load a template from a file.
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let text =
+            std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+
+        let mut registry = Handlebars::new();
+        registry.register_escape_fn(handlebars::no_escape);
+        registry.register_helper("heading", Box::new(heading));
+        registry
+            .register_template_string(NAME, text)
+            .map_err(|err| format!("{}: {}", path.display(), err))?;
+
+        Ok(Self { registry })
+    }
+
+    /// Render a release with the template.
+    pub fn render(&self, release: &ResolvedRelease) -> Result<String> {
+        Ok(self.registry.render(NAME, release)?)
+    }
+}
@@ -0,0 +1,161 @@
+use crate::release::{self, Author, AuthorLink, Change, OneOrMore, PullRequest, Release};
+
+use std::fmt;
+
+/// Write a heading underlined by `underline`, as long as the heading.
+fn write_heading(source: &mut dyn fmt::Write, heading: &str, underline: char) -> fmt::Result {
+    let line = underline.to_string().repeat(heading.chars().count());
+
+    writeln!(source, "{}\n{}\n", heading, line)
+}
+
+fn write_author(source: &mut dyn fmt::Write, rel: &Release, author: &Author) -> fmt::Result {
+    match rel.get_author_link(author) {
+        Some(AuthorLink::Profile(_)) => write!(source, "@{}", author.name()),
+        Some(AuthorLink::Mail(_)) | None => source.write_str(author.name()),
+    }
+}
+
+/// Write a list of changes of `part`, which is either the release `rel` or one of its components,
+/// under a heading.
+///
+/// The URLs of the commits and pull request of a change follow it on their own lines.
+fn write_list(
+    source: &mut dyn fmt::Write,
+    heading: &str,
+    rel: &Release,
+    part: &Release,
+    changes: &[Change],
+) -> fmt::Result {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    write_heading(source, heading, '-')?;
+
+    for change in changes {
+        let Change(category, name, OneOrMore(authors), OneOrMore(commits), _) = change;
+
+        let (name, pr) = PullRequest::split(name);
+
+        write!(source, "- [{}] {} (", category, name)?;
+
+        for (i, author) in authors.iter().enumerate() {
+            if i != 0 {
+                source.write_str(" ")?;
+            }
+
+            write_author(source, rel, author)?;
+        }
+
+        source.write_str(")")?;
+
+        for commit in commits {
+            write!(source, " {}", rel.short_hash(commit))?;
+        }
+
+        if let Some(PullRequest(number)) = pr {
+            write!(source, " {}", part.pull_request_ref(number))?;
+        }
+
+        writeln!(source)?;
+
+        for commit in commits {
+            writeln!(source, "  {}", part.commit_url(commit.hash()))?;
+        }
+
+        if let Some(PullRequest(number)) = pr {
+            writeln!(source, "  {}", part.pull_request_url(number))?;
+        }
+    }
+
+    writeln!(source)
+}
+
+/// Generate the output message from a [`Release`] as plain text, by writing to a source
+/// implementing [`std::fmt::Write`]
+///
+/// Nothing is marked up, so that the output suits emails, such as to mailing lists.
+/// Authors with profiles are written as `@name`, and commits as abbreviated hashes,
+/// with their URLs on their own lines.
+///
+/// [`Release`]: ../release/struct.Release.html
+/// [`std::fmt::Write`]: std::fmt::Write
+pub fn generate_text(source: &mut dyn fmt::Write, rel: &Release) -> fmt::Result {
+    if let Some(heading) = rel.heading() {
+        write_heading(source, &heading, '=')?;
+    }
+
+    if let Some(intro) = &rel.intro {
+        writeln!(source, "{}\n", intro.trim_end())?;
+    }
+
+    if let Some(summary) = &rel.summary {
+        writeln!(source, "{}\n", summary.trim_end())?;
+    }
+
+    writeln!(source, "{}\n", rel.strings.contributors())?;
+
+    for author in rel.sorted_authors() {
+        write!(source, "- ")?;
+        write_author(source, rel, author)?;
+        writeln!(source)?;
+    }
+
+    writeln!(source)?;
+
+    let mut reviewers = rel.reviewers.iter().collect::<Vec<_>>();
+    release::sort_authors(&mut reviewers);
+
+    if !reviewers.is_empty() {
+        writeln!(source, "{}\n", rel.strings.reviewers())?;
+
+        for reviewer in &reviewers {
+            write!(source, "- ")?;
+            write_author(source, rel, reviewer)?;
+            writeln!(source)?;
+        }
+
+        writeln!(source)?;
+    }
+
+    for section in rel.sections() {
+        write_list(source, &section.heading, rel, rel, section.changes)?;
+    }
+
+    for component in &rel.components {
+        let part = &component.release;
+
+        for section in rel.sections_of(part) {
+            let heading = format!("{}: {}", component.name, section.heading);
+            write_list(source, &heading, rel, part, section.changes)?;
+        }
+    }
+
+    if !rel.new_contributors.is_empty() {
+        write_heading(source, rel.strings.new_contributors(), '-')?;
+
+        for contributor in &rel.new_contributors {
+            write!(source, "- ")?;
+            write_author(source, rel, &contributor.author)?;
+            writeln!(
+                source,
+                " {}\n  {}",
+                rel.strings.first_contribution(),
+                rel.contribution_url(contributor)
+            )?;
+        }
+
+        writeln!(source)?;
+    }
+
+    if let Some(epilogue) = &rel.epilogue {
+        writeln!(source, "{}\n", epilogue.trim_end())?;
+    }
+
+    if let Some(url) = rel.full_changelog_url() {
+        writeln!(source, "{}: {}\n", rel.strings.full_changelog(), url)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,556 @@
+use crate::release::{self, Change, Release, Section};
+
+use serde_json::{Map, Value};
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// The keys of a release, as accepted in the input.
+const RELEASE_KEYS: &[&str] = &[
+    "repo_url",
+    "version",
+    "date",
+    "title",
+    "previous_version",
+    "hash_length",
+    "raw_names",
+    "author_url",
+    "author_fallback",
+    "commit_url",
+    "pull_request_url",
+    "compare_url",
+    "tag_url",
+    "forge",
+    "reviewers",
+    "new_contributors",
+    "intro",
+    "summary",
+    "epilogue",
+    "breaking",
+    "added",
+    "changed",
+    "deprecated",
+    "fixed",
+    "removed",
+    "security",
+    "sections",
+    "section_order",
+    "headings",
+    "strings",
+    "components",
+];
+
+/// The keys of a custom section, as accepted in the input.
+const CUSTOM_SECTION_KEYS: &[&str] = &["heading", "changes"];
+
+/// Describes a problem of the input of a release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The input is not valid JSON, or does not describe a release.
+    Invalid(String),
+    /// The release lacks the URL to its repository.
+    MissingRepoUrl,
+    /// A key that is not part of a release, such as a misspelled section.
+    UnknownKey(String),
+    /// A change that is not a list of a category, a name, authors, and commits.
+    InvalidChange(String),
+    /// A change with an empty category.
+    EmptyCategory,
+    /// A change with an empty list of authors or commits, named by the part.
+    Empty(&'static str),
+    /// A commit hash with characters other than hexadecimal digits.
+    InvalidHash(String),
+    /// A commit hash shorter than Git abbreviates hashes.
+    ShortHash(String, usize),
+    /// A length of abbreviated hashes shorter than hashes may be.
+    HashLength(u64),
+    /// A change with the same category and name as a previous change of the section,
+    /// at the line of the previous change.
+    DuplicateChange(usize),
+    /// An author that is an email address, rather than a username.
+    EmailAuthor(String),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::Invalid(err) => f.write_str(err),
+            Problem::MissingRepoUrl => f.write_str("missing `repo_url`"),
+            Problem::UnknownKey(key) => write!(f, "unknown key `{}`", key),
+            Problem::InvalidChange(err) => write!(f, "invalid change: {}", err),
+            Problem::EmptyCategory => f.write_str("empty category"),
+            Problem::Empty(part) => write!(f, "empty list of {}", part),
+            Problem::InvalidHash(hash) => write!(
+                f,
+                "commit hash `{}` is not made of hexadecimal digits",
+                hash
+            ),
+            Problem::ShortHash(hash, len) => write!(
+                f,
+                "commit hash `{}` is shorter than {} characters",
+                hash, len
+            ),
+            Problem::HashLength(len) => write!(
+                f,
+                "hash length {} is shorter than {} characters",
+                len,
+                release::MIN_HASH_LENGTH
+            ),
+            Problem::DuplicateChange(line) => write!(f, "duplicate of the change at line {}", line),
+            Problem::EmailAuthor(author) => {
+                write!(f, "author `{}` is an email address, not a username", author)
+            }
+        }
+    }
+}
+
+/// A problem of the input, at the line and column (both starting at 1) of the offending value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub problem: Problem,
+}
+
+/// Validate the JSON input of a release without generating output.
+///
+/// Commit hashes are checked against `hash_length`, as given on the command line or by the
+/// configuration, which takes precedence over the `hash_length` of the input.
+///
+/// Returns the problems of the input, in the order of their positions.
+pub fn validate(text: &str, hash_length: Option<usize>) -> Vec<Diagnostic> {
+    let value: Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        Err(err) => return vec![invalid(err)],
+    };
+
+    let mut validator = Validator {
+        positions: Locator::new(text).locate(),
+        diagnostics: Vec::new(),
+        malformed: false,
+        hash_length: release::DEFAULT_HASH_LENGTH,
+    };
+
+    let hash_length = hash_length
+        .map(|len| len as u64)
+        .or_else(|| value.get("hash_length").and_then(Value::as_u64));
+
+    if let Some(len) = hash_length {
+        if len < release::MIN_HASH_LENGTH as u64 {
+            validator.report("/hash_length", Problem::HashLength(len));
+        } else {
+            validator.hash_length = len as usize;
+        }
+    }
+
+    validator.release(&value, "", &[]);
+
+    // The remaining problems of the structure, such as values of the wrong type, are
+    // reported as they would be when generating.
+    if !validator.malformed {
+        if let Err(err) = serde_json::from_str::<Release>(text) {
+            validator.diagnostics.push(invalid(err));
+        }
+    }
+
+    validator.diagnostics.sort_by_key(|d| (d.line, d.column));
+    validator.diagnostics
+}
+
+fn invalid(err: serde_json::Error) -> Diagnostic {
+    // Strip the position from the message, as it is part of the diagnostic.
+    let message = err.to_string();
+    let message = match message.rfind(" at line ") {
+        Some(end) => message[..end].to_string(),
+        None => message,
+    };
+
+    Diagnostic {
+        line: err.line(),
+        column: err.column(),
+        problem: Problem::Invalid(message),
+    }
+}
+
+/// Returns whether an author looks like an email address, such as `jane@example.com`.
+fn is_email(author: &str) -> bool {
+    match author.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.contains('@')
+                && !author.contains(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+/// Returns the strings of a value that is a string or a list of strings, such as
+/// the authors and commits of a change.
+fn strings(value: &Value) -> Vec<&str> {
+    match value {
+        Value::String(s) => vec![s.as_str()],
+        Value::Array(values) => values.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+struct Validator {
+    /// The line and column of every value, by its JSON pointer.
+    positions: HashMap<String, (usize, usize)>,
+    diagnostics: Vec<Diagnostic>,
+    /// Whether a problem that prevents deserializing the release was reported.
+    malformed: bool,
+    /// The length that commit hashes are abbreviated to, which they should not be shorter than.
+    hash_length: usize,
+}
+
+impl Validator {
+    fn report(&mut self, pointer: &str, problem: Problem) {
+        let (line, column) = self.positions.get(pointer).copied().unwrap_or((1, 1));
+
+        self.diagnostics.push(Diagnostic {
+            line,
+            column,
+            problem,
+        });
+    }
+
+    fn release(&mut self, value: &Value, pointer: &str, extra_keys: &[&str]) {
+        let object = match value.as_object() {
+            Some(object) => object,
+            // Reported when deserializing the whole release.
+            None => return,
+        };
+
+        if object
+            .get("repo_url")
+            .and_then(Value::as_str)
+            .is_none_or(str::is_empty)
+        {
+            self.report(pointer, Problem::MissingRepoUrl);
+            self.malformed = true;
+        }
+
+        for key in object.keys() {
+            if !RELEASE_KEYS.contains(&key.as_str()) && !extra_keys.contains(&key.as_str()) {
+                self.report(&child(pointer, key), Problem::UnknownKey(key.clone()));
+            }
+        }
+
+        for section in Section::ALL.iter().copied() {
+            if let Some(Value::Array(changes)) = object.get(section.name()) {
+                self.section(changes, &child(pointer, section.name()));
+            }
+        }
+
+        self.custom_sections(object, pointer);
+        self.components(object, pointer);
+    }
+
+    fn custom_sections(&mut self, object: &Map<String, Value>, pointer: &str) {
+        let sections = match object.get("sections") {
+            Some(Value::Object(sections)) => sections,
+            _ => return,
+        };
+
+        let pointer = child(pointer, "sections");
+
+        for (name, section) in sections {
+            let pointer = child(&pointer, name);
+            let section = match section.as_object() {
+                Some(section) => section,
+                None => continue,
+            };
+
+            for key in section.keys() {
+                if !CUSTOM_SECTION_KEYS.contains(&key.as_str()) {
+                    self.report(&child(&pointer, key), Problem::UnknownKey(key.clone()));
+                }
+            }
+
+            if let Some(Value::Array(changes)) = section.get("changes") {
+                self.section(changes, &child(&pointer, "changes"));
+            }
+        }
+    }
+
+    fn components(&mut self, object: &Map<String, Value>, pointer: &str) {
+        let components = match object.get("components") {
+            Some(Value::Array(components)) => components,
+            _ => return,
+        };
+
+        let pointer = child(pointer, "components");
+
+        for (i, component) in components.iter().enumerate() {
+            self.release(component, &child(&pointer, &i.to_string()), &["name"]);
+        }
+    }
+
+    fn section(&mut self, changes: &[Value], pointer: &str) {
+        // The line of the first change of every category and name.
+        let mut seen: HashMap<(&str, &str), usize> = HashMap::new();
+
+        for (i, value) in changes.iter().enumerate() {
+            let pointer = child(pointer, &i.to_string());
+            let fields = value.as_array().map(Vec::as_slice).unwrap_or_default();
+
+            // Whether the change cannot be deserialized, because of a problem already reported.
+            let mut malformed = false;
+
+            for (index, part) in [(2, "authors"), (3, "commits")] {
+                match fields.get(index) {
+                    Some(Value::Array(values)) if values.is_empty() => {
+                        self.report(&child(&pointer, &index.to_string()), Problem::Empty(part));
+                        malformed = true;
+                    }
+                    Some(value) => {
+                        if index == 2 {
+                            self.authors(value, &child(&pointer, "2"));
+                        } else {
+                            malformed |= self.commits(value, &child(&pointer, "3"));
+                        }
+                    }
+                    None => {}
+                }
+            }
+
+            if malformed {
+                self.malformed = true;
+            } else if let Err(err) = serde_json::from_value::<Change>(value.clone()) {
+                self.report(&pointer, Problem::InvalidChange(err.to_string()));
+                self.malformed = true;
+                continue;
+            }
+
+            let category = fields.first().and_then(Value::as_str).unwrap_or_default();
+            let name = fields.get(1).and_then(Value::as_str).unwrap_or_default();
+
+            if fields.first().is_some_and(Value::is_string) && category.trim().is_empty() {
+                self.report(&child(&pointer, "0"), Problem::EmptyCategory);
+            }
+
+            let line = self.positions.get(&pointer).map_or(1, |&(line, _)| line);
+
+            match seen.get(&(category, name)) {
+                Some(&first) => self.report(&pointer, Problem::DuplicateChange(first)),
+                None => {
+                    seen.insert((category, name), line);
+                }
+            }
+        }
+    }
+
+    fn authors(&mut self, value: &Value, pointer: &str) {
+        for author in strings(value) {
+            if is_email(author) {
+                self.report(pointer, Problem::EmailAuthor(author.to_string()));
+            }
+        }
+    }
+
+    /// Check the commit hashes of a change, returning whether any is too short or not
+    /// hexadecimal, and so cannot be deserialized at all.
+    fn commits(&mut self, value: &Value, pointer: &str) -> bool {
+        let mut malformed = false;
+
+        for hash in strings(value) {
+            if !release::is_hex_hash(hash) {
+                self.report(pointer, Problem::InvalidHash(hash.to_string()));
+                malformed = true;
+                continue;
+            }
+
+            let len = hash.len();
+
+            if len < self.hash_length {
+                let problem = Problem::ShortHash(hash.to_string(), self.hash_length);
+                self.report(pointer, problem);
+                malformed |= len < release::MIN_HASH_LENGTH;
+            }
+        }
+
+        malformed
+    }
+}
+
+/// Returns the JSON pointer to a child of the value at a pointer.
+fn child(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Finds the line and column of every value of valid JSON, by its JSON pointer.
+struct Locator<'a> {
+    text: &'a [u8],
+    pos: usize,
+    line: usize,
+    column: usize,
+    positions: HashMap<String, (usize, usize)>,
+}
+
+impl<'a> Locator<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text: text.as_bytes(),
+            pos: 0,
+            line: 1,
+            column: 1,
+            positions: HashMap::new(),
+        }
+    }
+
+    fn locate(mut self) -> HashMap<String, (usize, usize)> {
+        self.value(String::new());
+        self.positions
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    fn advance(&mut self) {
+        if let Some(byte) = self.peek() {
+            self.pos += 1;
+
+            if byte == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else if self.peek().is_none_or(|next| next & 0xC0 != 0x80) {
+                // The last byte of a character.
+                self.column += 1;
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.advance();
+        }
+    }
+
+    fn value(&mut self, pointer: String) {
+        self.skip_whitespace();
+        self.positions
+            .insert(pointer.clone(), (self.line, self.column));
+
+        match self.peek() {
+            Some(b'{') => {
+                self.advance();
+
+                loop {
+                    self.skip_whitespace();
+
+                    if self.peek() != Some(b'"') {
+                        // The end of an empty object.
+                        self.advance();
+                        break;
+                    }
+
+                    let key = self.string();
+                    self.skip_whitespace();
+                    // The colon.
+                    self.advance();
+                    self.value(child(&pointer, &key));
+
+                    self.skip_whitespace();
+                    let separator = self.peek();
+                    self.advance();
+
+                    if separator != Some(b',') {
+                        break;
+                    }
+                }
+            }
+            Some(b'[') => {
+                self.advance();
+                self.skip_whitespace();
+
+                if self.peek() == Some(b']') {
+                    self.advance();
+                    return;
+                }
+
+                for i in 0.. {
+                    self.value(child(&pointer, &i.to_string()));
+
+                    self.skip_whitespace();
+                    let separator = self.peek();
+                    self.advance();
+
+                    if separator != Some(b',') {
+                        break;
+                    }
+                }
+            }
+            Some(b'"') => {
+                self.string();
+            }
+            _ => {
+                while self
+                    .peek()
+                    .is_some_and(|b| !matches!(b, b',' | b']' | b'}') && !b.is_ascii_whitespace())
+                {
+                    self.advance();
+                }
+            }
+        }
+    }
+
+    /// Skip a string, returning its contents.
+    fn string(&mut self) -> String {
+        let start = self.pos;
+        self.advance();
+
+        while let Some(byte) = self.peek() {
+            self.advance();
+
+            match byte {
+                b'\\' => self.advance(),
+                b'"' => break,
+                _ => {}
+            }
+        }
+
+        std::str::from_utf8(&self.text[start..self.pos])
+            .ok()
+            .and_then(|raw| serde_json::from_str(raw).ok())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems(text: &str) -> Vec<Problem> {
+        validate(text, None)
+            .into_iter()
+            .map(|diagnostic| diagnostic.problem)
+            .collect()
+    }
+
+    #[test]
+    fn valid() {
+        let text = r#"{"repo_url": "https://github.com/a/b",
+            "added": [["model", "Stickers", "Erk-", "2d3e585"]]}"#;
+
+        assert_eq!(problems(text), []);
+    }
+
+    #[test]
+    fn invalid_hash() {
+        let text = r#"{"repo_url": "https://github.com/a/b",
+            "added": [["model", "Stickers", "Erk-", "ééééé"]]}"#;
+
+        assert_eq!(problems(text), [Problem::InvalidHash("ééééé".to_string())]);
+    }
+
+    #[test]
+    fn empty_lists() {
+        let text = r#"{"repo_url": "https://github.com/a/b",
+            "added": [["model", "Stickers", [], "2d3e585"]]}"#;
+
+        assert_eq!(problems(text), [Problem::Empty("authors")]);
+    }
+}
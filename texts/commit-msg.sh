@@ -0,0 +1,28 @@
+#!/bin/sh
+# Installed by release-maker.
+#
+# Nudges commit messages towards a format that release-maker categorizes
+# automatically. It never rejects a commit.
+
+subject=$(grep -v '^#' "$1" | grep -v '^[[:space:]]*$' | head -n 1)
+
+case "$subject" in
+    Merge*|Revert*|fixup!*|squash!*|amend!*) exit 0 ;;
+esac
+
+if printf '%s\n' "$subject" | grep -Eq '^[A-Za-z]+(\([^)]+\))?!?: .+'; then
+    exit 0
+fi
+
+if grep -Eiq '^Changelog: *(added|changed|fixed|removed|none) *$' "$1"; then
+    exit 0
+fi
+
+cat >&2 <<'MSG'
+release-maker: the commit message neither follows Conventional Commits
+(such as "feat(scope): add a thing"), nor has a "Changelog: <section>"
+trailer (added, changed, fixed, removed, or none).
+It will be listed as "added" in the release notes, unless categorized by hand.
+MSG
+
+exit 0
@@ -0,0 +1,18 @@
+#!/bin/sh
+# Installed by release-maker.
+#
+# Appends a reminder of the Changelog trailer to new commit messages.
+
+case "$2" in
+    ""|template) ;;
+    *) exit 0 ;;
+esac
+
+if grep -q '^# Changelog:' "$1"; then
+    exit 0
+fi
+
+cat >> "$1" <<'MSG'
+# To categorize this commit in the release notes, add a trailer of:
+# Changelog: added|changed|fixed|removed|none
+MSG